@@ -0,0 +1,140 @@
+//! Measures the lookup of a registered limiter and the check of keys by
+//! concurrent threads.
+//!
+//! The checks are done by the [RocketGovernor] guard of requests dispatched
+//! by a multi-threaded runtime and by [RocketGovernor::check()], once with a
+//! key per thread and once with one key shared by all threads.
+//!
+//! Run with `cargo bench --bench registry`.
+
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    local::{asynchronous, blocking::Client},
+    routes,
+    tokio::runtime::Builder,
+};
+use rocket_governor::{
+    observer::Decision, Method, Quota, Registry, RocketGovernable, RocketGovernor,
+};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+    thread,
+    time::Instant,
+};
+
+const LOOKUPS: u32 = 1_000_000;
+const CHECKS: u32 = 200_000;
+const REQUESTS: u32 = 10_000;
+
+pub struct BenchGuard;
+
+impl<'r> RocketGovernable<'r> for BenchGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(1_000_000_000u32))
+    }
+}
+
+#[get("/")]
+fn route_bench(_limitguard: RocketGovernor<BenchGuard>) -> Status {
+    Status::Ok
+}
+
+/// Operations per second of all `threads` doing `ops` operations each by
+/// `f` with the index of the thread.
+fn run(threads: u32, ops: u32, f: impl Fn(u32) + Sync) -> f64 {
+    let start = Instant::now();
+    thread::scope(|s| {
+        for thread in 0..threads {
+            let f = &f;
+            let _ = s.spawn(move || {
+                for _ in 0..ops {
+                    f(thread);
+                }
+            });
+        }
+    });
+    f64::from(threads * ops) / start.elapsed().as_secs_f64()
+}
+
+/// Checks per second of the guard of `threads` concurrent clients with a key
+/// per client.
+fn run_guard(threads: u32) -> f64 {
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(threads as usize)
+        .enable_all()
+        .build()
+        .expect("no runtime");
+    runtime.block_on(async {
+        let client =
+            asynchronous::Client::untracked(rocket::build().mount("/", routes![route_bench]))
+                .await
+                .expect("no rocket instance");
+        let client = Arc::new(client);
+
+        let start = Instant::now();
+        let tasks = (0..threads)
+            .map(|thread| {
+                let client = Arc::clone(&client);
+                rocket::tokio::spawn(async move {
+                    let ip = Ipv4Addr::from(0x7f00_0100 + thread).to_string();
+                    for _ in 0..REQUESTS {
+                        let status = client
+                            .get("/")
+                            .header(Header::new("X-Real-IP", ip.clone()))
+                            .dispatch()
+                            .await
+                            .status();
+                        assert_eq!(Status::Ok, status);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.expect("task panicked");
+        }
+        f64::from(threads * REQUESTS) / start.elapsed().as_secs_f64()
+    })
+}
+
+fn main() {
+    let client = Client::untracked(rocket::build().mount("/", routes![route_bench]))
+        .expect("no rocket instance");
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", "127.0.0.1"));
+    assert_eq!(Status::Ok, req.dispatch().status());
+
+    let lookup = |_| {
+        assert!(Registry::get::<BenchGuard>(Method::Get, "route_bench").is_some());
+    };
+    let check = |thread| {
+        let ip = IpAddr::from(Ipv4Addr::from(0x7f00_0200 + thread));
+        let record = RocketGovernor::<BenchGuard>::check(Method::Get, "route_bench", ip);
+        assert_eq!(Decision::Allowed, record.decision());
+    };
+    let check_shared = |_| {
+        let ip = IpAddr::from(Ipv4Addr::new(127, 0, 3, 1));
+        let record = RocketGovernor::<BenchGuard>::check(Method::Get, "route_bench", ip);
+        assert_eq!(Decision::Allowed, record.decision());
+    };
+
+    // warm up
+    let _ = run(1, LOOKUPS, lookup);
+    let _ = run(1, CHECKS, check);
+
+    for threads in [1, 2, 4, 8] {
+        println!(
+            "threads {:>2}: {:>12.0} lookups/s {:>12.0} checks/s {:>12.0} shared key checks/s {:>10.0} guard checks/s",
+            threads,
+            run(threads, LOOKUPS, lookup),
+            run(threads, CHECKS, check),
+            run(threads, CHECKS, check_shared),
+            run_guard(threads),
+        );
+    }
+}
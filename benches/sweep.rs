@@ -0,0 +1,84 @@
+//! Compares the throughput of the guard with cleanup of the keys on every
+//! request against the cleanup by the background [KeySweeper].
+//!
+//! Run with `cargo bench --bench sweep`.
+
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    local::blocking::Client,
+    routes, Build, Rocket,
+};
+use rocket_governor::{KeySweeper, Method, Quota, Registry, RocketGovernable, RocketGovernor};
+use std::{
+    net::Ipv4Addr,
+    time::{Duration, Instant},
+};
+
+const KEYS: u32 = 10_000;
+const REQUESTS: u32 = 20_000;
+
+pub struct BenchGuard;
+
+impl<'r> RocketGovernable<'r> for BenchGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(1_000_000u32))
+    }
+}
+
+#[get("/")]
+fn route_bench(_limitguard: RocketGovernor<BenchGuard>) -> Status {
+    Status::Ok
+}
+
+fn launch_rocket() -> Rocket<Build> {
+    rocket::build()
+        .mount("/", routes![route_bench])
+        .attach(KeySweeper::default())
+}
+
+fn request(client: &Client, n: u32) -> Status {
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", Ipv4Addr::from(n).to_string()));
+    req.dispatch().status()
+}
+
+/// Requests per second with [KEYS] tracked keys.
+fn run(client: &Client, cleanup_per_request: bool) -> f64 {
+    let start = Instant::now();
+    for n in 0..REQUESTS {
+        assert_eq!(Status::Ok, request(client, n % KEYS));
+        if cleanup_per_request {
+            let _ = Registry::retain_recent();
+        }
+    }
+    f64::from(REQUESTS) / start.elapsed().as_secs_f64()
+}
+
+fn main() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    for n in 0..KEYS {
+        assert_eq!(Status::Ok, request(&client, n));
+    }
+    let limiter = Registry::get::<BenchGuard>(Method::Get, "route_bench").unwrap();
+    assert_eq!(KEYS as usize, limiter.len());
+
+    // warm up
+    let _ = run(&client, false);
+
+    let per_request = run(&client, true);
+    let sweeper = run(&client, false);
+
+    let start = Instant::now();
+    let _ = Registry::retain_recent();
+    let sweep: Duration = start.elapsed();
+
+    println!("tracked keys:            {:>10}", KEYS);
+    println!("cleanup per request:     {:>10.0} req/s", per_request);
+    println!("cleanup by KeySweeper:   {:>10.0} req/s", sweeper);
+    println!("single sweep:            {:>10.3?}", sweep);
+}
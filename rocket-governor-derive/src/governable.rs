@@ -0,0 +1,174 @@
+//! Implementation of `#[derive(RocketGovernable)]`.
+
+use crate::quota::QuotaSpec;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{parse_quote, DeriveInput, LitStr};
+
+/// HTTP methods of `rocket::http::Method`.
+const METHODS: [&str; 9] = [
+    "Get", "Put", "Post", "Delete", "Options", "Head", "Trace", "Connect", "Patch",
+];
+
+/// Quota of the routes with `name` and optional `method`.
+struct Route {
+    name: LitStr,
+    method: Option<Ident>,
+    quota: QuotaSpec,
+    /// Handler in scope named like the route, `None` for an `unchecked_name`.
+    handler: Option<Ident>,
+}
+
+/// Parses the `method` literal case insensitive to the variant of
+/// `rocket::http::Method`.
+pub(crate) fn parse_method(lit: &LitStr) -> syn::Result<Ident> {
+    let value = lit.value();
+    METHODS
+        .iter()
+        .find(|method| method.eq_ignore_ascii_case(&value))
+        .map(|method| Ident::new(method, lit.span()))
+        .ok_or_else(|| {
+            syn::Error::new(
+                lit.span(),
+                format!("invalid method `{value}`, expected e.g. \"GET\" or \"POST\""),
+            )
+        })
+}
+
+/// Derives `RocketGovernable` for `input` by its `#[governor(...)]`
+/// attributes.
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let mut default = None;
+    let mut routes: Vec<Route> = Vec::new();
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("governor"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if default.is_some() {
+                    return Err(meta.error("duplicate `default` quota"));
+                }
+                default = Some(QuotaSpec::parse_lit(&lit)?);
+                Ok(())
+            } else if meta.path.is_ident("route") {
+                let mut name = None;
+                let mut method = None;
+                let mut quota = None;
+                let mut unchecked = false;
+                meta.parse_nested_meta(|meta| {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let is_name = meta.path.is_ident("name");
+                    if is_name || meta.path.is_ident("unchecked_name") {
+                        if name.is_some() {
+                            return Err(meta.error("duplicate route name"));
+                        }
+                        name = Some(lit);
+                        unchecked = !is_name;
+                    } else if meta.path.is_ident("method") {
+                        method = Some(parse_method(&lit)?);
+                    } else if meta.path.is_ident("quota") {
+                        quota = Some(QuotaSpec::parse_lit(&lit)?);
+                    } else {
+                        return Err(
+                            meta.error("expected `name`, `unchecked_name`, `method` or `quota`")
+                        );
+                    }
+                    Ok(())
+                })?;
+                let (Some(name), Some(quota)) = (name, quota) else {
+                    return Err(
+                        meta.error("`route` requires `name` or `unchecked_name` and `quota`")
+                    );
+                };
+                if name.value().is_empty() {
+                    return Err(syn::Error::new(name.span(), "empty route name"));
+                }
+                let handler = if unchecked {
+                    None
+                } else {
+                    let handler = syn::parse_str::<Ident>(&name.value()).map_err(|_| {
+                        syn::Error::new(
+                            name.span(),
+                            "route name is not the name of a handler, \
+                             use `unchecked_name` to skip the check",
+                        )
+                    })?;
+                    Some(Ident::new(&handler.to_string(), name.span()))
+                };
+                let shadowed = routes.iter().any(|route| {
+                    route.name.value() == name.value()
+                        && (route.method.is_none() || route.method == method)
+                });
+                if shadowed {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "route is already declared for this method",
+                    ));
+                }
+                routes.push(Route {
+                    name,
+                    method,
+                    quota,
+                    handler,
+                });
+                Ok(())
+            } else {
+                Err(meta.error("expected `default` or `route`"))
+            }
+        })?;
+    }
+
+    let Some(default) = default else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "missing `#[governor(default = \"...\")]` quota",
+        ));
+    };
+
+    let arms = routes.iter().map(|route| {
+        let Route {
+            name,
+            method,
+            quota,
+            ..
+        } = route;
+        let method = match method {
+            Some(method) => quote!(::rocket_governor::Method::#method),
+            None => quote!(_),
+        };
+        quote!((#method, #name) => #quota,)
+    });
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote!('__r));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // a typo in a route name fails to compile, because there is no handler
+    let handlers = routes.iter().filter_map(|route| route.handler.as_ref());
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn handlers() {
+                #(let _ = #handlers;)*
+            }
+        };
+
+        impl #impl_generics ::rocket_governor::RocketGovernable<'__r> for #ident #ty_generics
+        #where_clause
+        {
+            fn quota(method: ::rocket_governor::Method, route_name: &str) -> ::rocket_governor::Quota {
+                match (method, route_name) {
+                    #(#arms)*
+                    _ => #default,
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,115 @@
+//! # rocket-governor-derive - derive macro for rocket-governor
+//!
+//! Provides `#[derive(RocketGovernable)]` for the trait
+//! [RocketGovernable] of [rocket-governor] with quotas declared by
+//! attributes instead of a hand-written `match` over route names.
+//!
+//! Use it by the feature __derive__ of [rocket-governor], which re-exports
+//! the macro.
+//!
+//! [rocket-governor]: https://docs.rs/rocket-governor/
+//! [RocketGovernable]: https://docs.rs/rocket-governor/latest/rocket_governor/trait.RocketGovernable.html
+
+#![deny(clippy::all)]
+#![deny(keyword_idents)]
+#![deny(missing_docs)]
+#![deny(non_ascii_idents)]
+#![deny(unreachable_pub)]
+#![deny(unsafe_code)]
+#![deny(unused_crate_dependencies)]
+#![deny(unused_qualifications)]
+#![deny(warnings)]
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+mod governable;
+mod quota;
+mod rate_limit;
+
+/// Derives `RocketGovernable` with the quotas of the `#[governor(...)]`
+/// attributes.
+///
+/// * `default = "<quota>"` is the required quota of all routes without an
+///   own quota.
+/// * `route(name = "<route name>", method = "<method>", quota = "<quota>")`
+///   is the quota of a route. The `method` is optional, without the quota
+///   applies to all methods of the route.
+///
+/// A quota is written like a `QuotaExpr` as
+/// `<requests>/[<amount>]<unit>[ burst <burst>]`, e.g. `"10/s"`, `"1/2s"` or
+/// `"100/min burst 20"`.
+///
+/// The quotas, methods and routes are validated at compile time: invalid
+/// quotas, unknown methods and routes declared twice fail to compile.
+/// The route `name` has to be the name of a handler function in scope,
+/// which is the route name of `RouteIdentity::Name`, so a typo fails to
+/// compile. Declare the route by `unchecked_name` instead of `name` to skip
+/// the check, e.g. for route names of another `RouteIdentity` or handlers
+/// out of scope.
+///
+/// All other methods of `RocketGovernable` keep their default
+/// implementation.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use rocket_governor::RocketGovernable;
+///
+/// #[derive(RocketGovernable)]
+/// #[governor(default = "10/s")]
+/// #[governor(route(name = "login", method = "POST", quota = "5/min"))]
+/// #[governor(route(unchecked_name = "/api/<path..>", quota = "100/min"))]
+/// pub struct RateLimitGuard;
+/// ```
+#[proc_macro_derive(RocketGovernable, attributes(governor))]
+pub fn derive_rocket_governable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    governable::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates the guard of a Rocket route handler with the quota of the
+/// arguments and adds it as request guard parameter to the handler.
+///
+/// The attribute needs to be placed above the route attribute of Rocket,
+/// e.g. `#[get(...)]`.
+///
+/// * `per_second = <n>`, `per_minute = <n>` or `per_hour = <n>`, or
+///   `quota = "<quota>"` like of `#[derive(RocketGovernable)]`, is the
+///   required quota.
+/// * `burst = <n>` is the optional burst size of the quota.
+/// * `key = "<parts>"` are the optional comma separated parts of the key:
+///   `ip`, `param:<name>` of the path or `query:<name>`, or `global` for one
+///   key of all requests. The key is the client IP address by default.
+///
+/// The guard is named after the handler in upper camel case with the
+/// suffix `RateLimit`, e.g. `LoginRateLimit` for `login`, and can be used
+/// e.g. with `Registry::get()`.
+///
+/// The arguments are validated at compile time including the path
+/// parameters of the key.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use rocket::{http::Status, post};
+/// use rocket_governor::rate_limit;
+///
+/// #[rate_limit(per_minute = 30, burst = 5, key = "ip")]
+/// #[post("/login")]
+/// fn login() -> Status {
+///     Status::Ok
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn rate_limit(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut rate_limit_args = rate_limit::Args::default();
+    let parser = syn::meta::parser(|meta| rate_limit_args.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as ItemFn);
+    rate_limit::rate_limit(rate_limit_args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,105 @@
+//! Quotas declared as string literal in the format of `QuotaExpr`, e.g.
+//! `"10/s"` or `"100/min burst 20"`.
+//!
+//! The grammar is a copy of the module of `QuotaExpr` in the crate
+//! `rocket-governor`, so the macros accept exactly the expressions of
+//! `QuotaExpr`.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use std::num::NonZeroU32;
+use syn::LitStr;
+
+mod grammar;
+
+pub(crate) use grammar::{HOUR, MINUTE, SECOND};
+
+/// Quota of `count` requests per `period` in nanoseconds with an optional
+/// `burst` validated at compile time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct QuotaSpec(grammar::QuotaParts);
+
+impl QuotaSpec {
+    /// Quota of `count` requests per `period` in nanoseconds.
+    pub(crate) fn new(count: NonZeroU32, period: u64) -> Result<Self, String> {
+        grammar::QuotaParts::new(count, period)
+            .map(Self)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Burst size of the quota, if declared.
+    pub(crate) fn burst(&self) -> Option<NonZeroU32> {
+        self.0.burst
+    }
+
+    /// Sets the `burst` size.
+    pub(crate) fn with_burst(self, burst: NonZeroU32) -> Self {
+        Self(grammar::QuotaParts {
+            burst: Some(burst),
+            ..self.0
+        })
+    }
+
+    /// Parses `<count>/[<amount>]<unit>[ burst <burst>]` like `QuotaExpr`.
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        grammar::QuotaParts::parse(s)
+            .map(Self)
+            .map_err(|e| format!("invalid quota `{s}`: {e}"))
+    }
+
+    /// Parses the string literal `lit` with the error at its span.
+    pub(crate) fn parse_lit(lit: &LitStr) -> syn::Result<Self> {
+        Self::parse(&lit.value()).map_err(|e| syn::Error::new(lit.span(), e))
+    }
+}
+
+impl ToTokens for QuotaSpec {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let grammar::QuotaParts {
+            count,
+            period,
+            burst,
+        } = self.0;
+        let burst = burst.unwrap_or(count).get();
+        let count = count.get();
+        tokens.extend(quote! {{
+            const QUOTA: ::rocket_governor::QuotaExpr =
+                ::rocket_governor::QuotaExpr::__validated(#count, #period, #burst);
+            QUOTA.quota()
+        }});
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuotaSpec, MINUTE, SECOND};
+    use std::num::NonZeroU32;
+
+    fn spec(count: u32, period: u64) -> QuotaSpec {
+        QuotaSpec::new(NonZeroU32::new(count).unwrap(), period).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(spec(10, SECOND)), QuotaSpec::parse("10/s"));
+        assert_eq!(Ok(spec(5, MINUTE)), QuotaSpec::parse(" 5 / minute "));
+        assert_eq!(Ok(spec(1, 2 * SECOND)), QuotaSpec::parse("1/2s"));
+        assert_eq!(
+            Ok(spec(100, MINUTE).with_burst(NonZeroU32::new(20).unwrap())),
+            QuotaSpec::parse("100/min burst 20")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(QuotaSpec::parse("10").is_err());
+        assert!(QuotaSpec::parse("0/s").is_err());
+        assert!(QuotaSpec::parse("-1/s").is_err());
+        assert!(QuotaSpec::parse("10/fortnight").is_err());
+        assert!(QuotaSpec::parse("ten/s").is_err());
+        assert!(QuotaSpec::parse("1/0s").is_err());
+        assert!(QuotaSpec::parse("2/ns").is_err());
+        assert!(QuotaSpec::parse("1/s burst 0").is_err());
+        assert!(QuotaSpec::new(NonZeroU32::new(2).unwrap(), 1).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Implementation of the attribute `#[rate_limit(...)]`.
+
+use crate::quota::{QuotaSpec, HOUR, MINUTE, SECOND};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::{
+    fmt::{self, Display},
+    num::NonZeroU32,
+};
+use syn::{meta::ParseNestedMeta, parse_quote, Attribute, ItemFn, LitInt, LitStr};
+
+/// Attributes of Rocket declaring a route with the URI as first argument.
+const ROUTE_ATTRIBUTES: [&str; 7] = ["get", "put", "post", "delete", "head", "options", "patch"];
+
+/// Arguments of `#[rate_limit(...)]`.
+#[derive(Default)]
+pub(crate) struct Args {
+    quota: Option<QuotaSpec>,
+    burst: Option<NonZeroU32>,
+    key: Option<LitStr>,
+}
+
+/// Part of the key by the argument `key`.
+#[derive(Debug, PartialEq, Eq)]
+enum KeyPart {
+    ClientIp,
+    Param(String),
+    Query(String),
+}
+
+impl Display for KeyPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyPart::ClientIp => f.write_str("ip"),
+            KeyPart::Param(name) => write!(f, "param:{name}"),
+            KeyPart::Query(name) => write!(f, "query:{name}"),
+        }
+    }
+}
+
+impl Args {
+    /// Parses one argument of `meta`.
+    pub(crate) fn parse(&mut self, meta: ParseNestedMeta<'_>) -> syn::Result<()> {
+        let period = if meta.path.is_ident("per_second") {
+            Some(SECOND)
+        } else if meta.path.is_ident("per_minute") {
+            Some(MINUTE)
+        } else if meta.path.is_ident("per_hour") {
+            Some(HOUR)
+        } else {
+            None
+        };
+
+        if let Some(period) = period {
+            let count = Self::nonzero(&meta)?;
+            let quota = QuotaSpec::new(count, period).map_err(|e| meta.error(e))?;
+            return self.set_quota(&meta, quota);
+        } else if meta.path.is_ident("quota") {
+            let lit: LitStr = meta.value()?.parse()?;
+            return self.set_quota(&meta, QuotaSpec::parse_lit(&lit)?);
+        } else if meta.path.is_ident("burst") {
+            self.burst = Some(Self::nonzero(&meta)?);
+        } else if meta.path.is_ident("key") {
+            self.key = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error(
+                "expected `per_second`, `per_minute`, `per_hour`, `quota`, `burst` or `key`",
+            ));
+        }
+        Ok(())
+    }
+
+    fn nonzero(meta: &ParseNestedMeta<'_>) -> syn::Result<NonZeroU32> {
+        let lit: LitInt = meta.value()?.parse()?;
+        NonZeroU32::new(lit.base10_parse()?)
+            .ok_or_else(|| syn::Error::new(lit.span(), "needs at least 1 request"))
+    }
+
+    fn set_quota(&mut self, meta: &ParseNestedMeta<'_>, quota: QuotaSpec) -> syn::Result<()> {
+        if self.quota.is_some() {
+            return Err(meta.error("only one quota is allowed"));
+        }
+        self.quota = Some(quota);
+        Ok(())
+    }
+}
+
+/// Parses the comma separated parts `ip`, `param:<name>` and
+/// `query:<name>` of `key`, or `global` for a key shared by all requests.
+fn parse_key(key: &str) -> Result<Vec<KeyPart>, String> {
+    if key.trim() == "global" {
+        return Ok(Vec::new());
+    }
+    let mut parts = Vec::new();
+    for part in key.split(',').map(str::trim) {
+        let part = match part.split_once(':') {
+            None if part == "ip" => KeyPart::ClientIp,
+            Some(("param", name)) if !name.trim().is_empty() => {
+                KeyPart::Param(name.trim().to_string())
+            }
+            Some(("query", name)) if !name.trim().is_empty() => {
+                KeyPart::Query(name.trim().to_string())
+            }
+            _ => {
+                return Err(format!(
+                    "invalid key part `{part}`, expected `ip`, `param:<name>`, `query:<name>` or `global`"
+                ))
+            }
+        };
+        if parts.contains(&part) {
+            return Err(format!("key part `{part}` is declared twice"));
+        }
+        parts.push(part);
+    }
+    Ok(parts)
+}
+
+/// URI of the route attribute of Rocket in `attrs`, if there is one with
+/// the URI as first argument.
+fn route_uri(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| {
+            attr.path().segments.last().map_or(false, |segment| {
+                ROUTE_ATTRIBUTES.contains(&&*segment.ident.to_string())
+            })
+        })
+        .find_map(|attr| {
+            attr.parse_args_with(|input: syn::parse::ParseStream<'_>| {
+                let uri: LitStr = input.parse()?;
+                let _: TokenStream = input.parse()?;
+                Ok(uri.value())
+            })
+            .ok()
+        })
+}
+
+/// Name of the path parameter of `segment`, e.g. `id` of `<id>` or `path`
+/// of `<path..>`.
+fn param_name(segment: &str) -> Option<&str> {
+    let param = segment.strip_prefix('<')?.strip_suffix('>')?;
+    Some(param.strip_suffix("..").unwrap_or(param))
+}
+
+/// Upper camel case of the snake case `name`.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Generates the guard of the route handler `item` with the quota of
+/// `args` and adds the guard as parameter.
+pub(crate) fn rate_limit(args: Args, mut item: ItemFn) -> syn::Result<TokenStream> {
+    let Some(mut quota) = args.quota else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "missing quota, expected e.g. `per_minute = 30` or `quota = \"30/min\"`",
+        ));
+    };
+
+    let key_parts = match &args.key {
+        Some(lit) => {
+            let parts = parse_key(&lit.value()).map_err(|e| syn::Error::new(lit.span(), e))?;
+            if let Some(uri) = route_uri(&item.attrs) {
+                let path = uri.split('?').next().unwrap_or_default();
+                for part in &parts {
+                    if let KeyPart::Param(name) = part {
+                        if !path.split('/').filter_map(param_name).any(|p| p == name) {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!("parameter `{name}` is not in the route `{uri}`"),
+                            ));
+                        }
+                    }
+                }
+            }
+            Some(parts)
+        }
+        None => None,
+    };
+
+    if let Some(burst) = args.burst {
+        if quota.burst().is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "burst is declared in `quota` and by `burst`",
+            ));
+        }
+        quota = quota.with_burst(burst);
+    }
+
+    let key_parts = key_parts.map(|parts| {
+        let parts = parts.iter().map(|part| match part {
+            KeyPart::ClientIp => quote!(::rocket_governor::KeyPart::ClientIp),
+            KeyPart::Param(name) => quote!(::rocket_governor::KeyPart::Param(#name)),
+            KeyPart::Query(name) => quote!(::rocket_governor::KeyPart::Query(#name)),
+        });
+        quote! {
+            fn key_parts(
+                _method: ::rocket_governor::Method,
+                _route_name: &str,
+            ) -> ::std::vec::Vec<::rocket_governor::KeyPart> {
+                ::std::vec![#(#parts),*]
+            }
+        }
+    });
+
+    let name = item.sig.ident.to_string();
+    let guard = format_ident!(
+        "{}RateLimit",
+        camel_case(&name),
+        span = item.sig.ident.span()
+    );
+    let vis = &item.vis;
+    let doc = format!("Rate limit guard of the route `{name}`.");
+
+    item.sig
+        .inputs
+        .push(parse_quote!(_rate_limit: ::rocket_governor::RocketGovernor<'_, #guard>));
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #guard;
+
+        impl<'r> ::rocket_governor::RocketGovernable<'r> for #guard {
+            fn quota(
+                _method: ::rocket_governor::Method,
+                _route_name: &str,
+            ) -> ::rocket_governor::Quota {
+                #quota
+            }
+
+            #key_parts
+        }
+
+        #item
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{camel_case, param_name, parse_key, KeyPart};
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(Ok(vec![KeyPart::ClientIp]), parse_key("ip"));
+        assert_eq!(Ok(vec![]), parse_key("global"));
+        assert_eq!(
+            Ok(vec![KeyPart::ClientIp, KeyPart::Param("id".to_string())]),
+            parse_key("ip, param:id")
+        );
+        assert_eq!(
+            Ok(vec![KeyPart::Query("token".to_string())]),
+            parse_key("query:token")
+        );
+        assert!(parse_key("").is_err());
+        assert!(parse_key("ip,ip").is_err());
+        assert!(parse_key("param:").is_err());
+        assert!(parse_key("header:x").is_err());
+    }
+
+    #[test]
+    fn test_param_name() {
+        assert_eq!(Some("id"), param_name("<id>"));
+        assert_eq!(Some("path"), param_name("<path..>"));
+        assert_eq!(None, param_name("accounts"));
+    }
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!("RouteTest", camel_case("route_test"));
+        assert_eq!("Login", camel_case("login"));
+        assert_eq!("ApiV2Users", camel_case("api_v2__users"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Adaptive scaling of quotas by the load of the server.
+
+use crate::load_monitor;
+use std::time::Duration;
+
+/// Signal of the load of the server, which decides if an [Adaptive] limiter
+/// is overloaded.
+#[derive(Clone, Copy, Debug)]
+pub enum LoadSignal {
+    /// Overloaded, if more requests are in flight than the provided number.
+    ///
+    /// Requires the [LoadMonitor](crate::LoadMonitor) fairing.
+    InFlight(usize),
+
+    /// Overloaded, if the moving average of the handler latency is above
+    /// the provided duration.
+    ///
+    /// Requires the [LoadMonitor](crate::LoadMonitor) fairing.
+    Latency(Duration),
+
+    /// Overloaded, if the provided function returns a load above `1.0`,
+    /// e.g. the utilization of a connection pool.
+    Load(fn() -> f64),
+}
+
+impl LoadSignal {
+    /// Returns `true` if the server is overloaded.
+    pub fn is_overloaded(&self) -> bool {
+        match self {
+            LoadSignal::InFlight(max) => load_monitor::in_flight() > *max,
+            LoadSignal::Latency(max) => load_monitor::latency() > *max,
+            LoadSignal::Load(load) => load() > 1.0,
+        }
+    }
+}
+
+/// Adaptive mode of a limiter, which scales its quota with a multiplier
+/// controlled by additive increase and multiplicative decrease (AIMD).
+///
+/// Every [interval](Self::interval()) the multiplier is decreased by the
+/// [decrease](Self::decrease()) factor, if the [LoadSignal] reports an
+/// overload, or else increased by the [increase](Self::increase()) step.
+/// The multiplier stays in the [bounds](Self::with_bounds()) and takes the
+/// values in steps of the increase from the lower bound, `1.0` and the upper
+/// bound. It starts at `1.0` or at the bound closest to `1.0`, if `1.0` is
+/// out of bounds.
+///
+/// The bounds must not need more than [MAX_STEPS](Self::MAX_STEPS) values
+/// with the increase, which is checked by
+/// [with_bounds()](Self::with_bounds()) and
+/// [with_increase()](Self::with_increase()). Set a larger increase before
+/// wider bounds.
+///
+/// The multiplier scales the rate and the burst size of the quota, so the
+/// period of the quota is kept as long as the burst size doesn't fall
+/// below 1.
+///
+/// See [RocketGovernable::adaptive()](crate::RocketGovernable::adaptive())
+/// and [Limiter::multiplier()](crate::Limiter::multiplier()).
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket_governor::{Adaptive, LoadSignal};
+/// use std::time::Duration;
+///
+/// let adaptive = Adaptive::new(LoadSignal::Latency(Duration::from_millis(200)))
+///     .with_bounds(0.25, 2.0)
+///     .with_increase(0.25)
+///     .with_decrease(0.5)
+///     .with_interval(Duration::from_secs(5));
+/// assert_eq!((0.25, 2.0), adaptive.bounds());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Adaptive {
+    signal: LoadSignal,
+    min: f64,
+    max: f64,
+    increase: f64,
+    decrease: f64,
+    interval: Duration,
+}
+
+impl Adaptive {
+    /// Maximum number of multipliers between the bounds.
+    pub const MAX_STEPS: usize = 64;
+
+    /// Creates the adaptive mode controlled by `signal` with the bounds
+    /// `0.25` and `2.0`, the increase `0.25`, the decrease `0.5` and the
+    /// interval of 1 second.
+    pub fn new(signal: LoadSignal) -> Self {
+        Self {
+            signal,
+            min: 0.25,
+            max: 2.0,
+            increase: 0.25,
+            decrease: 0.5,
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Bounds the multiplier to `min` and `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is not positive or `max` is less than `min`, or if
+    /// the bounds need more than [MAX_STEPS](Self::MAX_STEPS) values with
+    /// the [increase](Self::increase()).
+    pub fn with_bounds(self, min: f64, max: f64) -> Self {
+        assert!(min > 0.0 && max >= min, "invalid bounds: {}..{}", min, max);
+        Self { min, max, ..self }.assert_steps()
+    }
+
+    /// Increases the multiplier by `increase`, if not overloaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `increase` is not positive, or if the
+    /// [bounds](Self::bounds()) need more than [MAX_STEPS](Self::MAX_STEPS)
+    /// values with `increase`.
+    pub fn with_increase(self, increase: f64) -> Self {
+        assert!(increase > 0.0, "invalid increase: {}", increase);
+        Self { increase, ..self }.assert_steps()
+    }
+
+    /// Multiplies the multiplier by `decrease`, if overloaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `decrease` is not between `0.0` and `1.0`.
+    pub fn with_decrease(self, decrease: f64) -> Self {
+        assert!(
+            decrease > 0.0 && decrease < 1.0,
+            "invalid decrease: {}",
+            decrease
+        );
+        Self { decrease, ..self }
+    }
+
+    /// Adapts the multiplier at most every `interval`.
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// The [LoadSignal].
+    pub fn signal(&self) -> &LoadSignal {
+        &self.signal
+    }
+
+    /// Lower and upper bound of the multiplier.
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+
+    /// Step of the additive increase.
+    pub fn increase(&self) -> f64 {
+        self.increase
+    }
+
+    /// Factor of the multiplicative decrease.
+    pub fn decrease(&self) -> f64 {
+        self.decrease
+    }
+
+    /// Minimum time between adaptions of the multiplier.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Asserts that the bounds need at most [MAX_STEPS](Self::MAX_STEPS)
+    /// multipliers.
+    fn assert_steps(self) -> Self {
+        assert!(
+            self.steps().is_some(),
+            "adaptive bounds {}..{} need more than {} steps of increase {}",
+            self.min,
+            self.max,
+            Self::MAX_STEPS,
+            self.increase
+        );
+        self
+    }
+
+    /// The multipliers in steps of the increase from the lower bound to the
+    /// upper bound, `None` if more than [MAX_STEPS](Self::MAX_STEPS).
+    fn steps(&self) -> Option<Vec<f64>> {
+        let steps = (self.max - self.min) / self.increase + TOLERANCE;
+        if steps >= Self::MAX_STEPS as f64 {
+            return None;
+        }
+        let mut multipliers = (0..=steps as usize)
+            .map(|step| self.min + self.increase * step as f64)
+            .collect::<Vec<_>>();
+        for exact in [1.0, self.max] {
+            if exact < self.min || exact > self.max {
+                continue;
+            }
+            match multipliers
+                .iter_mut()
+                .find(|multiplier| (**multiplier - exact).abs() < TOLERANCE)
+            {
+                Some(multiplier) => *multiplier = exact,
+                None => multipliers.push(exact),
+            }
+        }
+        multipliers.sort_by(f64::total_cmp);
+        Some(multipliers).filter(|multipliers| multipliers.len() <= Self::MAX_STEPS)
+    }
+
+    /// The multipliers in steps of the increase from the lower bound to
+    /// the upper bound with exactly `1.0`, if in bounds, and the upper
+    /// bound.
+    ///
+    /// The number of multipliers is checked by the setters of the bounds
+    /// and of the increase, so there is at least the lower bound.
+    pub(crate) fn multipliers(&self) -> Vec<f64> {
+        self.steps().unwrap_or_else(|| vec![self.min])
+    }
+}
+
+/// Tolerance of the rounding errors of the steps of [Adaptive].
+const TOLERANCE: f64 = 1e-9;
@@ -0,0 +1,334 @@
+//! Provides [routes()] to inspect and reset the state of the rate limiters
+//! over HTTP.
+//!
+//! __Available__ only with __feature admin__!
+//!
+//! The routes are protected by a request guard of your choice, which is
+//! evaluated before any other processing. If the guard doesn't succeed, the
+//! request fails or is forwarded with the status of the guard.
+//!
+//! All routes respond with JSON and accept the optional query parameters
+//! `guard`, `method` and `route` to select the limiters by type name of the
+//! [RocketGovernable](crate::RocketGovernable) implementation, HTTP method
+//! and route name. Shared buckets have the method `null` and are selected by
+//! the bucket name in `route`.
+//!
+//! | Route                      | Description                                        |
+//! |----------------------------|----------------------------------------------------|
+//! | `GET /limiters`            | list the selected limiters with quota and number of keys |
+//! | `DELETE /limiters`         | clear all keys of the selected limiters            |
+//! | `GET /limiters/<key..>`    | state of the [LimitKey] `key` in the selected limiters |
+//! | `DELETE /limiters/<key..>` | reset the [LimitKey] `key` in the selected limiters |
+//!
+//! `DELETE /limiters` requires at least one of the query parameters or
+//! `all=true` to clear all limiters, otherwise it responds with
+//! [`Status::BadRequest`].
+//!
+//! The `key` is the path of the [LimitKey] as it is displayed, the client IP
+//! address followed by the percent-encoded parameters, e.g.
+//! `/limiters/192.0.2.1/a%25b` for the parameter `a%b` or `/limiters//42`
+//! for a key without client IP address. The path is decoded only once.
+//!
+//! The quota is listed with the burst size, the replenish interval and as
+//! [QuotaExpr].
+//!
+//! `DELETE` routes and `GET /limiters/<key..>` respond with
+//! [`Status::NotFound`] if there is no limiter selected.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rocket::{
+//!     http::Status,
+//!     launch,
+//!     request::{FromRequest, Outcome},
+//!     Request,
+//! };
+//!
+//! pub struct AdminGuard;
+//!
+//! #[rocket::async_trait]
+//! impl<'r> FromRequest<'r> for AdminGuard {
+//!     type Error = ();
+//!
+//!     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+//!         match request.headers().get_one("x-admin-token") {
+//!             Some("secret") => Outcome::Success(AdminGuard),
+//!             _ => Outcome::Error((Status::Unauthorized, ())),
+//!         }
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn launch_rocket() -> _ {
+//!     rocket::build().mount("/admin/ratelimit", rocket_governor::admin::routes::<AdminGuard>())
+//! }
+//! ```
+
+use crate::{
+    registry::{RegisteredRateLimiter, Registry},
+    LimitKey, QuotaExpr,
+};
+use rocket::{
+    async_trait,
+    http::{ContentType, Method, Status},
+    outcome::Outcome as GenericOutcome,
+    request::FromRequest,
+    route::{Handler, Outcome},
+    Data, Request, Route,
+};
+use std::{fmt::Write, marker::PhantomData, str::FromStr};
+
+/// Returns the admin routes protected by request guard `G`.
+///
+/// See [admin](self) module documentation.
+pub fn routes<G>() -> Vec<Route>
+where
+    G: for<'r> FromRequest<'r> + 'static,
+{
+    vec![
+        route::<G>(Method::Get, "/limiters", Action::List),
+        route::<G>(Method::Delete, "/limiters", Action::Clear),
+        route::<G>(Method::Get, "/limiters/<key..>", Action::ShowKey),
+        route::<G>(Method::Delete, "/limiters/<key..>", Action::ResetKey),
+    ]
+}
+
+fn route<G>(method: Method, uri: &str, action: Action) -> Route
+where
+    G: for<'r> FromRequest<'r> + 'static,
+{
+    let mut route = Route::new(
+        method,
+        uri,
+        AdminHandler::<G> {
+            action,
+            _guard: PhantomData,
+        },
+    );
+    route.name = Some(action.name().into());
+    route
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    List,
+    Clear,
+    ShowKey,
+    ResetKey,
+}
+
+impl Action {
+    fn name(self) -> &'static str {
+        match self {
+            Action::List => "rocket_governor_admin_list",
+            Action::Clear => "rocket_governor_admin_clear",
+            Action::ShowKey => "rocket_governor_admin_show_key",
+            Action::ResetKey => "rocket_governor_admin_reset_key",
+        }
+    }
+}
+
+struct AdminHandler<G> {
+    action: Action,
+    _guard: PhantomData<fn() -> G>,
+}
+
+impl<G> Clone for AdminHandler<G> {
+    fn clone(&self) -> Self {
+        Self {
+            action: self.action,
+            _guard: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<G> Handler for AdminHandler<G>
+where
+    G: for<'r> FromRequest<'r> + 'static,
+{
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match G::from_request(request).await {
+            GenericOutcome::Success(_) => {}
+            GenericOutcome::Error((status, _)) => return Outcome::Error(status),
+            GenericOutcome::Forward(status) => return Outcome::Forward((data, status)),
+        }
+
+        let limiters = match select(request) {
+            Ok(limiters) => limiters,
+            Err(status) => return Outcome::Error(status),
+        };
+
+        let json = match self.action {
+            Action::List => list(&limiters),
+            Action::Clear => {
+                if is_selected(request) != Ok(true) {
+                    return Outcome::Error(Status::BadRequest);
+                }
+                if limiters.is_empty() {
+                    return Outcome::Error(Status::NotFound);
+                }
+                for limiter in &limiters {
+                    limiter.clear();
+                }
+                format!("{{\"cleared\":{}}}", limiters.len())
+            }
+            Action::ShowKey | Action::ResetKey => {
+                let key = match raw_key(request).map(LimitKey::from_str) {
+                    Some(Ok(key)) => key,
+                    _ => return Outcome::Error(Status::BadRequest),
+                };
+                if limiters.is_empty() {
+                    return Outcome::Error(Status::NotFound);
+                }
+                if let Action::ShowKey = self.action {
+                    show_key(&limiters, &key)
+                } else {
+                    let reset = limiters
+                        .iter()
+                        .filter(|limiter| limiter.reset_key(&key))
+                        .count();
+                    format!("{{\"reset\":{}}}", reset)
+                }
+            }
+        };
+
+        Outcome::from(request, (ContentType::JSON, json))
+    }
+}
+
+/// Registered limiters selected by the query parameters.
+fn select(request: &Request<'_>) -> Result<Vec<RegisteredRateLimiter>, Status> {
+    let query = |name: &str| match request.query_value::<&str>(name) {
+        Some(Ok(value)) => Ok(Some(value)),
+        Some(Err(_)) => Err(Status::BadRequest),
+        None => Ok(None),
+    };
+    let guard = query("guard")?;
+    let method = query("method")?
+        .map(|m| Method::from_str(&m.to_ascii_uppercase()).map_err(|_| Status::BadRequest))
+        .transpose()?;
+    let route = query("route")?;
+
+    let mut limiters = Registry::limiters()
+        .into_iter()
+        .filter(|limiter| {
+            guard.map_or(true, |g| g == limiter.guard)
+                && method.map_or(true, |m| Some(m) == limiter.method)
+                && route.map_or(true, |r| r == &*limiter.route_name)
+        })
+        .collect::<Vec<_>>();
+    limiters.sort_by(|a, b| {
+        (a.guard, a.method_str(), &a.route_name).cmp(&(b.guard, b.method_str(), &b.route_name))
+    });
+
+    Ok(limiters)
+}
+
+/// Returns `true` if the request selects limiters by query parameters or
+/// all limiters by `all=true`.
+fn is_selected(request: &Request<'_>) -> Result<bool, Status> {
+    let all = request
+        .query_value::<bool>("all")
+        .transpose()
+        .map_err(|_| Status::BadRequest)?;
+    Ok(all == Some(true)
+        || ["guard", "method", "route"]
+            .iter()
+            .any(|name| request.query_value::<&str>(name).is_some()))
+}
+
+/// The [LimitKey] in the raw path after `/limiters/`, which is still
+/// percent-encoded, so that it is decoded once by [LimitKey::from_str()].
+fn raw_key<'r>(request: &'r Request<'_>) -> Option<&'r str> {
+    let base = request.route()?.uri.base().trim_end_matches('/');
+    request
+        .uri()
+        .path()
+        .raw()
+        .as_str()
+        .strip_prefix(base)?
+        .strip_prefix("/limiters/")
+        .filter(|key| !key.is_empty())
+}
+
+fn list(limiters: &[RegisteredRateLimiter]) -> String {
+    let mut json = String::from("[");
+    for (n, limiter) in limiters.iter().enumerate() {
+        if n > 0 {
+            json.push(',');
+        }
+        let _ = write!(
+            json,
+            "{{{},\"quota\":{{\"burst\":{},\"replenish_interval_ms\":{},\"expr\":\"{}\"}},\"keys\":{}}}",
+            identity(limiter),
+            limiter.quota.burst_size(),
+            limiter.quota.replenish_interval().as_millis(),
+            QuotaExpr::from(limiter.quota),
+            limiter.len()
+        );
+    }
+    json.push(']');
+    json
+}
+
+fn show_key(limiters: &[RegisteredRateLimiter], key: &LimitKey) -> String {
+    let mut json = String::from("[");
+    for (n, limiter) in limiters.iter().enumerate() {
+        if n > 0 {
+            json.push(',');
+        }
+        let state = limiter.peek_key(key);
+        let _ = write!(
+            json,
+            "{{{},\"key\":\"{}\",\"remaining\":{},\"wait_ms\":{}}}",
+            identity(limiter),
+            escape(&key.to_string()),
+            state.remaining,
+            state.wait_time.as_millis()
+        );
+    }
+    json.push(']');
+    json
+}
+
+/// JSON members identifying the `limiter`.
+fn identity(limiter: &RegisteredRateLimiter) -> String {
+    let method = limiter
+        .method
+        .map_or_else(|| String::from("null"), |m| format!("\"{}\"", m));
+    format!(
+        "\"guard\":\"{}\",\"method\":{},\"route\":\"{}\"",
+        escape(limiter.guard),
+        method,
+        escape(&limiter.route_name)
+    )
+}
+
+/// Escape a JSON string value.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!("route", escape("route"));
+        assert_eq!(r#"a\"b\\c\u000ad"#, escape("a\"b\\c\nd"));
+    }
+}
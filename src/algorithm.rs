@@ -0,0 +1,32 @@
+//! Rate limiting algorithms of the limiters.
+
+/// Algorithm of a rate limiter, which interprets the [Quota](crate::Quota).
+///
+/// The window algorithms count the requests in windows of the length of the
+/// [Quota](crate::Quota) period, which is the replenish interval multiplied
+/// by the burst size, e.g. 1000 requests per hour for
+/// `Quota::per_hour(nonzero(1000))`. The windows are aligned to the
+/// [UNIX epoch](std::time::UNIX_EPOCH), so windows of a minute, an hour or a
+/// day are calendar minutes, hours or days in UTC.
+///
+/// See [RocketGovernable::algorithm()](crate::RocketGovernable::algorithm()).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// Generic cell rate algorithm of [governor], which replenishes the
+    /// [Quota](crate::Quota) continuously and allows bursts up to the burst
+    /// size.
+    ///
+    /// [governor]: https://docs.rs/governor/
+    #[default]
+    Gcra,
+    /// Counts the requests per window and resets the count at the end of the
+    /// window.
+    ///
+    /// A client can do up to twice the limit around the end of a window.
+    FixedWindow,
+    /// Weighs the count of the previous window by its share in the sliding
+    /// window ending now and adds the count of the current window.
+    ///
+    /// This smooths the bursts at the end of a fixed window.
+    SlidingWindow,
+}
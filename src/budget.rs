@@ -0,0 +1,152 @@
+//! Usage budgets with calendar-aligned periods.
+
+use rocket::time::{Date, Duration as TimeDuration, Month, OffsetDateTime, Time, UtcOffset};
+use std::{num::NonZeroU32, time::Duration};
+
+/// Calendar period of a [Budget].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BudgetPeriod {
+    /// Calendar day, starting at 00:00.
+    Day,
+    /// Calendar week, starting on Monday at 00:00.
+    Week,
+    /// Calendar month, starting on the 1st at 00:00.
+    Month,
+}
+
+/// Usage budget of a number of requests per calendar period, e.g. 50,000
+/// requests per month.
+///
+/// The budget of a key is reset at the start of every period at the fixed
+/// [UtcOffset] of the budget (default UTC).
+/// Time zones with rules like daylight saving time are not supported, the
+/// periods of a zone changing its offset are aligned to the fixed offset
+/// only, e.g. shifted by an hour in summer time.
+///
+/// See [RocketGovernable::budget()](crate::RocketGovernable::budget()).
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket::time::{Date, Month::*, PrimitiveDateTime, Time, UtcOffset};
+/// use rocket_governor::{Budget, NonZeroU32};
+///
+/// let at = |date: Date, hour, offset| {
+///     PrimitiveDateTime::new(date, Time::from_hms(hour, 0, 0).unwrap()).assume_offset(offset)
+/// };
+/// let utc = UtcOffset::UTC;
+/// let jan_31 = Date::from_calendar_date(2023, January, 31).unwrap();
+/// let feb_1 = Date::from_calendar_date(2023, February, 1).unwrap();
+///
+/// let budget = Budget::per_month(NonZeroU32::new(50_000).unwrap());
+/// assert_eq!(at(feb_1, 0, utc), budget.next_reset(at(jan_31, 23, utc)));
+///
+/// let plus_2 = UtcOffset::from_hms(2, 0, 0).unwrap();
+/// let budget = Budget::per_day(NonZeroU32::new(1_000).unwrap()).with_offset(plus_2);
+/// assert_eq!(at(feb_1, 0, plus_2), budget.next_reset(at(jan_31, 21, utc)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Budget {
+    limit: NonZeroU32,
+    period: BudgetPeriod,
+    offset: UtcOffset,
+}
+
+impl Budget {
+    /// Creates the budget of `limit` requests per `period`.
+    pub fn new(limit: NonZeroU32, period: BudgetPeriod) -> Self {
+        Self {
+            limit,
+            period,
+            offset: UtcOffset::UTC,
+        }
+    }
+
+    /// Creates the budget of `limit` requests per calendar day.
+    pub fn per_day(limit: NonZeroU32) -> Self {
+        Self::new(limit, BudgetPeriod::Day)
+    }
+
+    /// Creates the budget of `limit` requests per calendar week.
+    pub fn per_week(limit: NonZeroU32) -> Self {
+        Self::new(limit, BudgetPeriod::Week)
+    }
+
+    /// Creates the budget of `limit` requests per calendar month.
+    pub fn per_month(limit: NonZeroU32) -> Self {
+        Self::new(limit, BudgetPeriod::Month)
+    }
+
+    /// Aligns the periods to the fixed `offset` from UTC.
+    pub fn with_offset(self, offset: UtcOffset) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// Number of requests per period.
+    pub fn limit(&self) -> NonZeroU32 {
+        self.limit
+    }
+
+    /// The [BudgetPeriod].
+    pub fn period(&self) -> BudgetPeriod {
+        self.period
+    }
+
+    /// Fixed offset from UTC of the periods.
+    pub fn offset(&self) -> UtcOffset {
+        self.offset
+    }
+
+    /// Start of the period following `now`, when the budget is reset.
+    pub fn next_reset(&self, now: OffsetDateTime) -> OffsetDateTime {
+        let today = now.to_offset(self.offset).date();
+        let start = match self.period {
+            BudgetPeriod::Day => today.next_day(),
+            BudgetPeriod::Week => {
+                let monday =
+                    today - TimeDuration::days(today.weekday().number_days_from_monday().into());
+                Some(monday + TimeDuration::weeks(1))
+            }
+            BudgetPeriod::Month => {
+                let (year, month) = match today.month() {
+                    Month::December => (today.year() + 1, Month::January),
+                    month => (today.year(), month.next()),
+                };
+                Date::from_calendar_date(year, month, 1).ok()
+            }
+        };
+
+        start
+            .unwrap_or(Date::MAX)
+            .with_time(Time::MIDNIGHT)
+            .assume_offset(self.offset)
+    }
+}
+
+/// State of the [Budget] of a key.
+///
+/// See [ReqState::budget()](crate::ReqState::budget()) and
+/// [DecisionRecord::budget()](crate::observer::DecisionRecord::budget()).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetState {
+    pub(crate) budget: Budget,
+    pub(crate) remaining: u32,
+    pub(crate) reset: Duration,
+}
+
+impl BudgetState {
+    /// The [Budget] of the limiter.
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// Number of requests left in the current period.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Time until the budget is reset.
+    pub fn reset(&self) -> Duration {
+        self.reset
+    }
+}
@@ -0,0 +1,115 @@
+//! Guard marker types with the [Quota] by const generics.
+
+use crate::{Method, Quota, RocketGovernable};
+
+/// Implements a guard marker type with the quota of `$constructor`.
+macro_rules! const_quota {
+    ($(#[$doc:meta])* $name:ident, $constructor:ident, $per:literal) => {
+        $(#[$doc])*
+        ///
+        /// Every instantiation is a guard of its own with its own limiters in
+        /// the [Registry](crate::Registry). All other methods of
+        /// [RocketGovernable] keep their default implementation.
+        ///
+        /// A quota or burst of 0 fails to compile.
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $name<const N: u32, const B: u32 = N>;
+
+        impl<const N: u32, const B: u32> $name<N, B> {
+            /// Checked at compile time on use of the quota.
+            const NONZERO: () = assert!(N > 0 && B > 0, "quota and burst need to be at least 1");
+        }
+
+        impl<'r, const N: u32, const B: u32> RocketGovernable<'r> for $name<N, B> {
+            #[doc = concat!("Returns `N` requests per ", $per, " with a burst of `B`.")]
+            fn quota(method: Method, route_name: &str) -> Quota {
+                let (_, _) = (method, route_name); // unused warning
+                let () = Self::NONZERO;
+
+                Quota::$constructor(Self::nonzero(N)).allow_burst(Self::nonzero(B))
+            }
+        }
+    };
+}
+
+const_quota!(
+    /// Guard marker type limiting to `N` requests per second with a burst of
+    /// `B` requests, which defaults to `N`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket::{get, http::Status};
+    /// use rocket_governor::{PerSecond, RocketGovernor};
+    ///
+    /// #[get("/")]
+    /// fn route_example(_limitguard: RocketGovernor<PerSecond<5>>) -> Status {
+    ///     Status::Ok
+    /// }
+    /// ```
+    PerSecond,
+    per_second,
+    "second"
+);
+
+const_quota!(
+    /// Guard marker type limiting to `N` requests per minute with a burst of
+    /// `B` requests, which defaults to `N`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket::{get, http::Status};
+    /// use rocket_governor::{PerMinute, RocketGovernor};
+    ///
+    /// #[get("/")]
+    /// fn route_example(_limitguard: RocketGovernor<PerMinute<30, 10>>) -> Status {
+    ///     Status::Ok
+    /// }
+    /// ```
+    PerMinute,
+    per_minute,
+    "minute"
+);
+
+const_quota!(
+    /// Guard marker type limiting to `N` requests per hour with a burst of
+    /// `B` requests, which defaults to `N`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket::{get, http::Status};
+    /// use rocket_governor::{PerHour, RocketGovernor};
+    ///
+    /// #[get("/")]
+    /// fn route_example(_limitguard: RocketGovernor<PerHour<100>>) -> Status {
+    ///     Status::Ok
+    /// }
+    /// ```
+    PerHour,
+    per_hour,
+    "hour"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{PerHour, PerMinute, PerSecond};
+    use crate::{Method, RocketGovernable};
+    use std::time::Duration;
+
+    #[test]
+    fn test_quota() {
+        let quota = PerSecond::<5>::quota(Method::Get, "route");
+        assert_eq!(5, quota.burst_size().get());
+        assert_eq!(Duration::from_millis(200), quota.replenish_interval());
+
+        let quota = PerMinute::<30, 10>::quota(Method::Get, "route");
+        assert_eq!(10, quota.burst_size().get());
+        assert_eq!(Duration::from_secs(2), quota.replenish_interval());
+
+        let quota = PerHour::<100>::quota(Method::Get, "route");
+        assert_eq!(100, quota.burst_size().get());
+        assert_eq!(Duration::from_secs(36), quota.replenish_interval());
+    }
+}
@@ -30,6 +30,22 @@ pub enum Header {
     /// Header provides the time in seconds when a request to the route is not
     /// rate limited and the rate limiter bucket is full again.
     XRateLimitReset(u64),
+
+    /// Header provides the time in seconds a request would have been rate
+    /// limited, if the route is governed in shadow mode.
+    XRateLimitShadow(u64),
+
+    /// Header provides the number of requests of the usage
+    /// [Budget](crate::Budget) per period.
+    XRateLimitBudgetLimit(u64),
+
+    /// Header provides how many requests are left in the current period of
+    /// the usage [Budget](crate::Budget).
+    XRateLimitBudgetRemaining(u64),
+
+    /// Header provides the time in seconds when the usage
+    /// [Budget](crate::Budget) is reset.
+    XRateLimitBudgetReset(u64),
 }
 
 /// Standard header for status 429 Too Many Requests
@@ -57,6 +73,22 @@ pub const X_RATELIMIT_REMAINING: &str = "x-ratelimit-remaining";
 /// rate limited and the rate limiter bucket is full again.
 pub const X_RATELIMIT_RESET: &str = "x-ratelimit-reset";
 
+/// Header provides the time in seconds, rounded up, a request would have
+/// been rate limited, if the route is governed in shadow mode.
+pub const X_RATELIMIT_SHADOW: &str = "x-ratelimit-shadow";
+
+/// Header provides the number of requests of the usage
+/// [Budget](crate::Budget) per period.
+pub const X_RATELIMIT_BUDGET_LIMIT: &str = "x-ratelimit-budget-limit";
+
+/// Header provides how many requests are left in the current period of the
+/// usage [Budget](crate::Budget).
+pub const X_RATELIMIT_BUDGET_REMAINING: &str = "x-ratelimit-budget-remaining";
+
+/// Header provides the time in seconds when the usage
+/// [Budget](crate::Budget) is reset.
+pub const X_RATELIMIT_BUDGET_RESET: &str = "x-ratelimit-budget-reset";
+
 #[doc(hidden)]
 impl From<Header> for http::Header<'_> {
     fn from(header: Header) -> Self {
@@ -70,6 +102,16 @@ impl From<Header> for http::Header<'_> {
                 http::Header::new(X_RATELIMIT_REMAINING, remaining.to_string())
             }
             Header::XRateLimitReset(sec) => http::Header::new(X_RATELIMIT_RESET, sec.to_string()),
+            Header::XRateLimitShadow(sec) => http::Header::new(X_RATELIMIT_SHADOW, sec.to_string()),
+            Header::XRateLimitBudgetLimit(limit) => {
+                http::Header::new(X_RATELIMIT_BUDGET_LIMIT, limit.to_string())
+            }
+            Header::XRateLimitBudgetRemaining(remaining) => {
+                http::Header::new(X_RATELIMIT_BUDGET_REMAINING, remaining.to_string())
+            }
+            Header::XRateLimitBudgetReset(sec) => {
+                http::Header::new(X_RATELIMIT_BUDGET_RESET, sec.to_string())
+            }
         }
     }
 }
@@ -78,7 +120,9 @@ impl From<Header> for http::Header<'_> {
 mod tests {
     use super::Header;
     use super::{
-        RETRY_AFTER, X_RATELIMIT_ERROR, X_RATELIMIT_LIMIT, X_RATELIMIT_REMAINING, X_RATELIMIT_RESET,
+        RETRY_AFTER, X_RATELIMIT_BUDGET_LIMIT, X_RATELIMIT_BUDGET_REMAINING,
+        X_RATELIMIT_BUDGET_RESET, X_RATELIMIT_ERROR, X_RATELIMIT_LIMIT, X_RATELIMIT_REMAINING,
+        X_RATELIMIT_RESET, X_RATELIMIT_SHADOW,
     };
     use rocket::http;
     use std::str::FromStr;
@@ -104,5 +148,21 @@ mod tests {
         let h: http::Header = Header::XRateLimitReset(5).into();
         assert_eq!(X_RATELIMIT_RESET, h.name());
         assert_eq!(5, u64::from_str(h.value()).unwrap());
+
+        let h: http::Header = Header::XRateLimitShadow(3).into();
+        assert_eq!(X_RATELIMIT_SHADOW, h.name());
+        assert_eq!(3, u64::from_str(h.value()).unwrap());
+
+        let h: http::Header = Header::XRateLimitBudgetLimit(1000).into();
+        assert_eq!(X_RATELIMIT_BUDGET_LIMIT, h.name());
+        assert_eq!(1000, u64::from_str(h.value()).unwrap());
+
+        let h: http::Header = Header::XRateLimitBudgetRemaining(999).into();
+        assert_eq!(X_RATELIMIT_BUDGET_REMAINING, h.name());
+        assert_eq!(999, u64::from_str(h.value()).unwrap());
+
+        let h: http::Header = Header::XRateLimitBudgetReset(3600).into();
+        assert_eq!(X_RATELIMIT_BUDGET_RESET, h.name());
+        assert_eq!(3600, u64::from_str(h.value()).unwrap());
     }
 }
@@ -0,0 +1,121 @@
+//! Provides [`Fairing`](rocket::fairing::Fairing) in the implementation
+//! [`KeySweeper`] which is [attachable](rocket::Rocket::attach()) to
+//! [`Rocket`](rocket::Rocket)-instance.
+
+use crate::{logger::debug, Registry};
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    tokio::{
+        self,
+        time::{self, MissedTickBehavior},
+    },
+    Orbit, Rocket,
+};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Number of running `KeySweeper` tasks.
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns `true` if a `KeySweeper` task removes the idle keys.
+#[inline]
+pub(crate) fn is_running() -> bool {
+    RUNNING.load(Ordering::Relaxed) > 0
+}
+
+/// Provides [`Fairing`](rocket::fairing::Fairing) implementation
+/// which is [attachable](rocket::Rocket::attach()) to
+/// [`Rocket`](rocket::Rocket)-instance.
+///
+/// `KeySweeper` spawns a background task on
+/// [liftoff](rocket::fairing::Fairing::on_liftoff()), which removes the idle
+/// keys of all rate limiters in the [Registry] and the budget usage of passed
+/// periods periodically.
+/// A key is idle, if its state is equal to the state of a new key, so that
+/// removing it doesn't change any rate limit decision.
+///
+/// The task is stopped on [shutdown](rocket::Shutdown) of Rocket.
+///
+/// Without a running `KeySweeper` the limiters remove their idle keys
+/// themselves after a number of new keys, which grows with the number of
+/// tracked keys. This keeps the memory bounded, but some requests pay for
+/// the removal.
+///
+/// The default interval is 60 seconds.
+///
+/// ## Example usage
+///
+/// ```rust
+/// use rocket;
+/// use rocket_governor::KeySweeper;
+/// use std::time::Duration;
+///
+/// #[rocket::launch]
+/// fn launch_rocket() -> _ {
+///     rocket::build().attach(KeySweeper::new(Duration::from_secs(30)))
+/// }
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct KeySweeper {
+    interval: Duration,
+}
+
+impl KeySweeper {
+    /// Creates the `KeySweeper` removing idle keys every `interval`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on liftoff if `interval` is zero.
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+
+    /// Interval of removing idle keys.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+impl Default for KeySweeper {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60))
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for KeySweeper {
+    fn info(&self) -> Info {
+        Info {
+            name: "RateLimit Key Sweeper",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    /// Spawns the background task removing idle keys until shutdown.
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let shutdown = rocket.shutdown();
+        let mut ticker = time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        // detached, the task ends with the shutdown of Rocket
+        let _ = RUNNING.fetch_add(1, Ordering::Relaxed);
+        drop(tokio::spawn(async move {
+            tokio::pin!(shutdown);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        #[allow(unused_variables)] // only used in debug
+                        let removed = Registry::retain_recent();
+                        debug!("removed {} idle keys", removed);
+                    }
+                    _ = &mut shutdown => break,
+                }
+            }
+            let _ = RUNNING.fetch_sub(1, Ordering::Relaxed);
+            debug!("key sweeper stopped");
+        }));
+    }
+}
@@ -32,10 +32,60 @@
 //! }
 //! ```
 //!
+//! Attach the [KeySweeper] fairing to remove the keys of idle clients from
+//! the limiters in the background.
+//!
+//! Use the non-failing guard [RateLimitStatus] to handle limited requests in
+//! the route, e.g. with a degraded response.
+//!
 //! See [rocket-governor] Github project for more information.
 //!
 //! ## Features
 //!
+//! ### Optional feature __admin__
+//!
+//! There is the optional feature __admin__ which provides
+//! [routes](admin::routes()) to inspect and reset the state of the
+//! rate limiters. The routes are protected by a request guard of your choice.
+//!
+//! See API documentation of module [admin].
+//!
+//! For usage depend on it in Cargo.toml
+//! ```toml
+//! [dependencies]
+//! rocket-governor = { version = "...", features = ["admin"] }
+//! ```
+//!
+//! ### Optional feature __derive__
+//!
+//! There is the optional feature __derive__ which provides
+//! `#[derive(RocketGovernable)]` to declare the quotas by attributes,
+//! which are validated at compile time including the route names against
+//! the handlers in scope:
+//!
+//! ```rust,ignore
+//! #[derive(RocketGovernable)]
+//! #[governor(default = "10/s", route(name = "login", method = "POST", quota = "5/min"))]
+//! pub struct RateLimitGuard;
+//! ```
+//!
+//! The attribute `#[rate_limit(...)]` generates the guard of a route handler
+//! and adds it as parameter of the handler:
+//!
+//! ```rust,ignore
+//! #[rate_limit(per_minute = 30, burst = 5, key = "ip")]
+//! #[post("/login")]
+//! fn login() -> Status {
+//!     Status::Ok
+//! }
+//! ```
+//!
+//! For usage depend on it in Cargo.toml
+//! ```toml
+//! [dependencies]
+//! rocket-governor = { version = "...", features = ["derive"] }
+//! ```
+//!
 //! ### Optional feature __limit_info__
 //!
 //! There is the optional feature __limit_info__ which enables reporting about
@@ -64,6 +114,43 @@
 //! rocket-governor = { version = "...", features = ["logger"] }
 //! ```
 //!
+//! ### Optional feature __metrics__
+//!
+//! There is the optional feature __metrics__ which counts the decisions of
+//! the rate limiters and tracks the number of keys per limiter.
+//!
+//! The metrics are provided in Prometheus text format by the Rocket route
+//! [rocket_governor_metrics()], which need to be mounted.
+//!
+//! For usage depend on it in Cargo.toml
+//! ```toml
+//! [dependencies]
+//! rocket-governor = { version = "...", features = ["metrics"] }
+//! ```
+//!
+//! ### Optional feature __serde__
+//!
+//! There is the optional feature __serde__ which (de)serializes a
+//! [QuotaExpr] as string like `"100/min burst 20"`, e.g. in config files.
+//!
+//! For usage depend on it in Cargo.toml
+//! ```toml
+//! [dependencies]
+//! rocket-governor = { version = "...", features = ["serde"] }
+//! ```
+//!
+//! ### Optional feature __tracing__
+//!
+//! There is the optional feature __tracing__ which emits spans and events
+//! with structured fields (`guard`, `method`, `route`, `key`, `remaining`,
+//! `wait_ms`, `decision`) by the [tracing](https://docs.rs/tracing/) crate.
+//!
+//! For usage depend on it in Cargo.toml
+//! ```toml
+//! [dependencies]
+//! rocket-governor = { version = "...", features = ["tracing"] }
+//! ```
+//!
 //! [governor]: https://docs.rs/governor/
 //! [rocket]: https://docs.rs/rocket/
 //! [rocket-governor]: https://github.com/kolbma/rocket-governor/
@@ -79,14 +166,32 @@
 //#![deny(unused_results)]
 #![deny(warnings)]
 
-use governor::clock::{Clock, DefaultClock};
+pub use adaptive::{Adaptive, LoadSignal};
+pub use algorithm::Algorithm;
+pub use budget::{Budget, BudgetPeriod, BudgetState};
+pub use const_quota::{PerHour, PerMinute, PerSecond};
+use governor::clock::DefaultClock;
 pub use governor::Quota;
+pub use key_sweeper::KeySweeper;
 use lazy_static::lazy_static;
 pub use limit_error::LimitError;
 #[cfg(feature = "limit_info")]
 pub use limit_header_gen::LimitHeaderGen;
-use logger::{error, info, trace};
-use registry::Registry;
+use limit_key::KeyStrategy;
+pub use limit_key::{KeyPart, LimitKey};
+pub use load_monitor::LoadMonitor;
+pub use load_shedder::{rocket_governor_unavailable_catcher, LoadShed, LoadShedder};
+use logger::{debug, error, info, trace};
+#[cfg(feature = "metrics")]
+pub use metrics::rocket_governor_metrics;
+use observer::{Decision, DecisionRecord};
+pub use priority::Priority;
+pub use queue_mode::QueueMode;
+pub use quota_expr::{QuotaExpr, QuotaExprError};
+pub use quota_schedule::QuotaSchedule;
+pub use rate_limit_status::RateLimitStatus;
+use registry::{Allowed, Denied, LimiterConfig, RegisteredRoute};
+pub use registry::{KeyState, Limiter, Registry};
 #[cfg(feature = "limit_info")]
 pub use req_state::ReqState;
 pub use rocket::http::Method;
@@ -94,21 +199,51 @@ use rocket::{
     async_trait, catch,
     http::Status,
     request::{FromRequest, Outcome},
+    tokio::time::timeout,
     Request,
 };
 pub use rocket_governable::RocketGovernable;
-use std::marker::PhantomData;
-pub use std::num::NonZeroU32;
+#[cfg(feature = "derive")]
+pub use rocket_governor_derive::{rate_limit, RocketGovernable};
+pub use route_identity::RouteIdentity;
+pub use std::num::{NonZeroU32, NonZeroUsize};
+use std::{
+    any::type_name,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+#[cfg(test)]
+use trybuild as _; // used by the compile-fail tests of the derive macros
 
+mod adaptive;
+#[cfg(feature = "admin")]
+pub mod admin;
+mod algorithm;
+mod budget;
+mod const_quota;
 pub mod header;
+mod key_sweeper;
 mod limit_error;
 #[cfg(feature = "limit_info")]
 mod limit_header_gen;
+mod limit_key;
+mod load_monitor;
+mod load_shedder;
 mod logger;
+#[cfg(feature = "metrics")]
+mod metrics;
+pub mod observer;
+mod priority;
+mod queue_mode;
+mod quota_expr;
+mod quota_schedule;
+mod rate_limit_status;
 mod registry;
 #[cfg(feature = "limit_info")]
 mod req_state;
 mod rocket_governable;
+mod route_identity;
 
 /// Generic [RocketGovernor] implementation.
 ///
@@ -141,72 +276,10 @@ where
 {
     /// Handler used in `FromRequest::from_request(request: &'r Request)`.
     #[inline(always)]
-    pub fn handle_from_request(request: &'r Request) -> Outcome<Self, LimitError> {
-        let res = request.local_cache(|| {
-            if let Some(route) = request.route() {
-                if let Some(route_name) = &route.name {
-                    let limiter = Registry::get_or_insert::<T>(
-                        route.method,
-                        route_name,
-                        T::quota(route.method, route_name),
-                    );
-                    if let Some(client_ip) = request.client_ip() {
-                        let limit_check_res = limiter.check_key(&client_ip);
-                        match limit_check_res {
-                            Ok(state) => {
-                                #[allow(unused_variables)] // only used in trace or when feature limit_info
-                                let request_capacity = state.remaining_burst_capacity();
-                                trace!(
-                                    "not governed ip {} method {} route {}: remaining request capacity {}",
-                                    &client_ip,
-                                    &route.method,
-                                    route_name,
-                                    request_capacity
-                                );
-
-                                #[cfg(feature = "limit_info")] {
-                                    // `local_cache` lookup works by type and so it doesn't work to catch
-                                    // `LimitError` and handle different Ok objects:
-                                    // See https://rocket.rs/v0.5/guide/state/#request-local-state
-                                    // State wrapper is so cached separate...
-                                    let req_state = ReqState::new(state.quota(), request_capacity);
-                                    let is_req_state_allowed = T::limit_info_allow(Some(route.method), Some(route_name), &req_state);
-                                    if is_req_state_allowed {
-                                        // For safety and speed this is used by default in a limited way, see:
-                                        // * Information disclosure:
-                                        //   https://datatracker.ietf.org/doc/html/draft-ietf-httpapi-ratelimit-headers#section-6.2
-                                        //
-                                        let _ = request.local_cache(|| req_state);
-                                    }
-                                }
-
-                                Ok(()) // needs to be something not changing during request
-                            }
-                            Err(notuntil) => {
-                                let wait_time = notuntil.wait_time_from(CLOCK.now()).as_secs();
-                                info!(
-                                    "ip {} method {} route {} limited {} sec",
-                                    &client_ip, &route.method, route_name, &wait_time
-                                );
-                                Err(LimitError::GovernedRequest(wait_time, notuntil.quota()))
-                            }
-                        }
-                    } else {
-                        error!(
-                            "missing ip - method {} route {}: request: {:?}",
-                            &route.method, route_name, request
-                        );
-                        Err(LimitError::MissingClientIpAddr)
-                    }
-                } else {
-                    error!("route without name: request: {:?}", request);
-                    Err(LimitError::MissingRouteName)
-                }
-            } else {
-                error!("routing failure: request: {:?}", request);
-                Err(LimitError::MissingRoute)
-            }
-        });
+    pub async fn handle_from_request(request: &'r Request<'_>) -> Outcome<Self, LimitError> {
+        let res = request
+            .local_cache_async(async { Self::check_request(request).await.0 })
+            .await;
 
         match res {
             Ok(_) => {
@@ -230,7 +303,9 @@ where
             Err(e) => {
                 let e = e.clone();
                 match e {
-                    LimitError::GovernedRequest(_, _) => {
+                    LimitError::GovernedRequest(_, _)
+                    | LimitError::GovernedWindow(_, _, _)
+                    | LimitError::BudgetExhausted(_, _) => {
                         Outcome::Error((Status::TooManyRequests, e))
                     }
                     _ => Outcome::Error((Status::BadRequest, e)),
@@ -238,6 +313,495 @@ where
             }
         }
     }
+
+    /// Checks and consumes the rate limit of `request` and reports the
+    /// decision.
+    ///
+    /// Returns the result of the guard and the [DecisionRecord].
+    pub(crate) async fn check_request(
+        request: &'r Request<'_>,
+    ) -> (Result<(), LimitError>, DecisionRecord) {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "rocket_governor",
+            guard = type_name::<T>(),
+            method = tracing::field::Empty,
+            route = tracing::field::Empty,
+        );
+
+        let check = async {
+            Self::check_routed(
+                request,
+                #[cfg(feature = "tracing")]
+                &span,
+            )
+            .await
+        };
+        #[cfg(feature = "tracing")]
+        let check = tracing::Instrument::instrument(check, span.clone());
+
+        check.await
+    }
+
+    async fn check_routed(
+        request: &'r Request<'_>,
+        #[cfg(feature = "tracing")] span: &tracing::Span,
+    ) -> (Result<(), LimitError>, DecisionRecord) {
+        if let Some(route) = request.route() {
+            #[cfg(feature = "tracing")]
+            span.record("method", route.method.as_str());
+
+            if let Some(route_name) = T::route_identity().of(route) {
+                #[cfg(feature = "tracing")]
+                span.record("route", route_name);
+
+                let registered =
+                    Registry::get_or_insert::<T>(route.method, route_name, true, || {
+                        LimiterConfig {
+                            key_strategy: Some(KeyStrategy::new(&T::key_parts(
+                                route.method,
+                                route_name,
+                            ))),
+                            ..Self::limiter_config(route.method, route_name)
+                        }
+                    });
+                let key = registered
+                    .key_strategy
+                    .as_ref()
+                    .map_or(Err(LimitError::MissingClientIpAddr), |key_strategy| {
+                        key_strategy.key(request, route)
+                    });
+                match key {
+                    Ok(key) => {
+                        let priority = T::priority(request);
+                        let (result, record) = Self::check_key_queued(
+                            route.method,
+                            route_name,
+                            &registered,
+                            key,
+                            priority,
+                        )
+                        .await;
+
+                        #[cfg(feature = "limit_info")]
+                        {
+                            // `local_cache` lookup works by type and so it doesn't work to catch
+                            // `LimitError` and handle different Ok objects:
+                            // See https://rocket.rs/v0.5/guide/state/#request-local-state
+                            // State wrapper is so cached separate...
+                            let req_state = match (record.decision, record.quota) {
+                                (Decision::Allowed | Decision::Delayed, Some(quota)) => {
+                                    Some(ReqState::new(quota, record.remaining))
+                                }
+                                (Decision::Shadowed, Some(quota)) => {
+                                    // round up, so that a sub-second wait isn't 0
+                                    let wait = record.wait_time;
+                                    let wait = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+                                    Some(ReqState::new_shadow(quota, wait))
+                                }
+                                _ => None,
+                            }
+                            .map(|req_state| req_state.with_budget(record.budget));
+                            if let Some(req_state) = req_state {
+                                let is_req_state_allowed = T::limit_info_allow(
+                                    Some(route.method),
+                                    Some(route_name),
+                                    &req_state,
+                                );
+                                if is_req_state_allowed {
+                                    // For safety and speed this is used by default in a limited way, see:
+                                    // * Information disclosure:
+                                    //   https://datatracker.ietf.org/doc/html/draft-ietf-httpapi-ratelimit-headers#section-6.2
+                                    //
+                                    let _ = request.local_cache(|| req_state);
+                                }
+                            }
+                        }
+
+                        (result, record) // needs to be something not changing during request
+                    }
+                    Err(err) => {
+                        error!(
+                            "no key {:?} - method {} route {}: request: {:?}",
+                            &err, &route.method, route_name, request
+                        );
+
+                        let record = Self::report(
+                            Some(&registered),
+                            DecisionRecord {
+                                guard: type_name::<T>(),
+                                method: Some(route.method),
+                                route_name: Some(Arc::clone(&registered.route_name)),
+                                key: None,
+                                quota: Some(registered.limiter.quota),
+                                remaining: 0,
+                                wait_time: Duration::ZERO,
+                                budget: None,
+                                decision: Decision::Errored,
+                            },
+                        );
+
+                        (Err(err), record)
+                    }
+                }
+            } else {
+                error!("route without name: request: {:?}", request);
+
+                let record = Self::report(
+                    None,
+                    DecisionRecord {
+                        guard: type_name::<T>(),
+                        method: Some(route.method),
+                        route_name: None,
+                        key: None,
+                        quota: None,
+                        remaining: 0,
+                        wait_time: Duration::ZERO,
+                        budget: None,
+                        decision: Decision::Errored,
+                    },
+                );
+
+                (Err(LimitError::MissingRouteName), record)
+            }
+        } else {
+            error!("routing failure: request: {:?}", request);
+
+            let record = Self::report(
+                None,
+                DecisionRecord {
+                    guard: type_name::<T>(),
+                    method: None,
+                    route_name: None,
+                    key: None,
+                    quota: None,
+                    remaining: 0,
+                    wait_time: Duration::ZERO,
+                    budget: None,
+                    decision: Decision::Errored,
+                },
+            );
+
+            (Err(LimitError::MissingRoute), record)
+        }
+    }
+
+    /// The [LimiterConfig] of `T` without key strategy.
+    fn limiter_config(method: Method, route_name: &str) -> LimiterConfig {
+        LimiterConfig {
+            quota: T::quota(method, route_name),
+            max_keys: T::max_keys(method, route_name),
+            bucket: T::bucket(method, route_name),
+            algorithm: T::algorithm(method, route_name),
+            budget: T::budget(method, route_name),
+            schedule: T::quota_schedule(method, route_name),
+            adaptive: T::adaptive(method, route_name),
+            reserved: Priority::ALL
+                .map(|priority| T::reserved_capacity(method, route_name, priority)),
+            key_strategy: None,
+        }
+    }
+
+    /// Checks and consumes the rate limit of `key` of `priority` in the
+    /// limiter of the `registered` route and reports the decision.
+    fn check_key(
+        method: Method,
+        route_name: &str,
+        registered: &RegisteredRoute,
+        key: LimitKey,
+        priority: Priority,
+    ) -> (Result<(), LimitError>, DecisionRecord) {
+        let limit_check_res = registered.limiter.check_key(&key, priority);
+        Self::decide(
+            method,
+            route_name,
+            registered,
+            key,
+            limit_check_res,
+            Duration::ZERO,
+        )
+    }
+
+    /// Checks and consumes the rate limit of `key` like
+    /// [check_key()](Self::check_key()), but a limited request waits until
+    /// allowed in [queue mode](RocketGovernable::queue_mode()).
+    async fn check_key_queued(
+        method: Method,
+        route_name: &str,
+        registered: &RegisteredRoute,
+        key: LimitKey,
+        priority: Priority,
+    ) -> (Result<(), LimitError>, DecisionRecord) {
+        let mut limit_check_res = registered.limiter.check_key(&key, priority);
+        let mut delay = Duration::ZERO;
+
+        if let Err(denied) = &limit_check_res {
+            let queue_mode = T::queue_mode(method, route_name)
+                .filter(|queue_mode| denied.wait_time <= queue_mode.max_delay())
+                .filter(|_| denied.budget.is_none())
+                .filter(|_| !T::shadow(method, route_name));
+            if let Some(queue_mode) = queue_mode {
+                let waiter = registered
+                    .limiter
+                    .enter_queue(&key, queue_mode.max_waiting());
+                // every request waiting ahead takes one replenish interval
+                let projected = waiter.as_ref().and_then(|waiter| {
+                    let ahead = u32::try_from(waiter.ahead()).ok()?;
+                    denied
+                        .quota
+                        .replenish_interval()
+                        .checked_mul(ahead)?
+                        .checked_add(denied.wait_time)?
+                        .checked_add(queue_mode.jitter())
+                });
+                match projected {
+                    Some(projected) if projected <= queue_mode.max_delay() => {
+                        debug!(
+                            "key {} method {} route {} waiting",
+                            &key, &method, route_name
+                        );
+
+                        let start = Instant::now();
+                        let ready = timeout(
+                            queue_mode.max_delay(),
+                            registered
+                                .limiter
+                                .until_key_ready(&key, priority, queue_mode.jitter()),
+                        )
+                        .await;
+                        limit_check_res = match ready {
+                            Ok(res) => res,
+                            Err(_) => registered.limiter.check_key(&key, priority),
+                        };
+                        delay = start.elapsed();
+                    }
+                    Some(_) => {
+                        info!(
+                            "key {} method {} route {} projected wait exceeds max delay",
+                            &key, &method, route_name
+                        );
+                    }
+                    None => {
+                        info!(
+                            "key {} method {} route {} too many waiting",
+                            &key, &method, route_name
+                        );
+                    }
+                }
+            }
+        }
+
+        Self::decide(method, route_name, registered, key, limit_check_res, delay)
+    }
+
+    /// Reports the decision about the result `limit_check_res` of the check
+    /// of `key`, which has been `delay`ed in queue mode.
+    fn decide(
+        method: Method,
+        route_name: &str,
+        registered: &RegisteredRoute,
+        key: LimitKey,
+        limit_check_res: Result<Allowed, Denied>,
+        delay: Duration,
+    ) -> (Result<(), LimitError>, DecisionRecord) {
+        match limit_check_res {
+            Ok(allowed) => {
+                let request_capacity = allowed.remaining;
+                trace!(
+                    "not governed key {} method {} route {}: remaining request capacity {}",
+                    &key,
+                    &method,
+                    route_name,
+                    request_capacity
+                );
+
+                let record = Self::report(
+                    Some(registered),
+                    DecisionRecord {
+                        guard: type_name::<T>(),
+                        method: Some(method),
+                        route_name: Some(Arc::clone(&registered.route_name)),
+                        key: Some(key),
+                        quota: Some(allowed.quota),
+                        remaining: request_capacity,
+                        wait_time: delay,
+                        budget: allowed.budget,
+                        decision: if delay.is_zero() {
+                            Decision::Allowed
+                        } else {
+                            Decision::Delayed
+                        },
+                    },
+                );
+
+                (Ok(()), record)
+            }
+            Err(denied) => {
+                let wait = denied.wait_time;
+                let wait_time = wait.as_secs();
+                let is_shadow = T::shadow(method, route_name);
+
+                if is_shadow {
+                    info!(
+                        "shadow: key {} method {} route {} would be limited {} sec",
+                        &key, &method, route_name, &wait_time
+                    );
+                } else {
+                    info!(
+                        "key {} method {} route {} limited {} sec",
+                        &key, &method, route_name, &wait_time
+                    );
+                }
+
+                let record = Self::report(
+                    Some(registered),
+                    DecisionRecord {
+                        guard: type_name::<T>(),
+                        method: Some(method),
+                        route_name: Some(Arc::clone(&registered.route_name)),
+                        key: Some(key),
+                        quota: Some(denied.quota),
+                        remaining: 0,
+                        wait_time: wait,
+                        budget: denied.budget,
+                        decision: if is_shadow {
+                            Decision::Shadowed
+                        } else {
+                            Decision::Limited
+                        },
+                    },
+                );
+
+                if is_shadow {
+                    (Ok(()), record) // shadow mode never rejects
+                } else {
+                    let limit_error = match (denied.budget, denied.reset) {
+                        (Some(budget), _) => {
+                            LimitError::BudgetExhausted(wait_time, *budget.budget())
+                        }
+                        (None, Some(reset)) => {
+                            LimitError::GovernedWindow(wait_time, reset.as_secs(), denied.quota)
+                        }
+                        (None, None) => LimitError::GovernedRequest(wait_time, denied.quota),
+                    };
+                    (Err(limit_error), record)
+                }
+            }
+        }
+    }
+
+    /// Count the decision with feature __metrics__ and notify the
+    /// [DecisionListeners](observer::DecisionListener).
+    ///
+    /// Returns the `record`.
+    #[inline]
+    fn report(registered: Option<&RegisteredRoute>, record: DecisionRecord) -> DecisionRecord {
+        #[cfg(feature = "metrics")]
+        match registered {
+            Some(registered) => registered.counters.inc(record.decision),
+            None => metrics::inc_unrouted_error(record.guard, record.method),
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = registered; // unused warning
+
+        #[cfg(feature = "tracing")]
+        {
+            macro_rules! decision_event {
+                ($level:expr) => {
+                    tracing::event!(
+                        $level,
+                        guard = record.guard,
+                        method = record.method.map(|m| m.as_str()),
+                        route = record.route_name.as_deref(),
+                        key = record.key.as_ref().map(tracing::field::display),
+                        remaining = record.remaining,
+                        wait_ms = record.wait_time.as_millis() as u64,
+                        decision = record.decision.as_str(),
+                    )
+                };
+            }
+
+            match record.decision {
+                Decision::Allowed => decision_event!(tracing::Level::TRACE),
+                Decision::Delayed => decision_event!(tracing::Level::DEBUG),
+                Decision::Limited | Decision::Shadowed => decision_event!(tracing::Level::INFO),
+                Decision::Errored => decision_event!(tracing::Level::ERROR),
+            }
+        }
+
+        observer::notify(T::listener(), &record);
+
+        record
+    }
+}
+
+impl<'r, T> RocketGovernor<'r, T>
+where
+    T: RocketGovernable<'r>,
+{
+    /// Checks and consumes the rate limit of `key` like the guard, but
+    /// outside of a request, e.g. per message of a WebSocket or on
+    /// submission of a background job.
+    ///
+    /// The limiter of `T` for `method` and `route_name` is used and
+    /// registered on first use, so `route_name` doesn't need to be a route of
+    /// Rocket. The [KeyParts](KeyPart) of `T` aren't applied, `key` is used
+    /// as it is.
+    ///
+    /// The decision is reported like for requests and returned as
+    /// [DecisionRecord]. A [limited](Decision::Limited) call should be
+    /// rejected.
+    /// The check doesn't wait in [queue mode](RocketGovernable::queue_mode())
+    /// and is of the default [Priority].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{
+    ///     observer::Decision, Method, Quota, RocketGovernable, RocketGovernor,
+    /// };
+    /// use std::net::IpAddr;
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(1u32))
+    ///     }
+    /// }
+    ///
+    /// let client: IpAddr = "192.0.2.1".parse().unwrap();
+    ///
+    /// let record = RocketGovernor::<RateLimitGuard>::check(Method::Get, "ws_message", client);
+    /// assert_eq!(Decision::Allowed, record.decision());
+    ///
+    /// let record = RocketGovernor::<RateLimitGuard>::check(Method::Get, "ws_message", client);
+    /// assert_eq!(Decision::Limited, record.decision());
+    /// println!("retry in {:?}", record.wait_time());
+    /// ```
+    pub fn check(method: Method, route_name: &str, key: impl Into<LimitKey>) -> DecisionRecord {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "rocket_governor",
+            guard = type_name::<T>(),
+            method = method.as_str(),
+            route = route_name,
+        )
+        .entered();
+
+        let registered = Registry::get_or_insert::<T>(method, route_name, false, || {
+            Self::limiter_config(method, route_name)
+        });
+
+        Self::check_key(
+            method,
+            route_name,
+            &registered,
+            key.into(),
+            Priority::default(),
+        )
+        .1
+    }
 }
 
 #[doc(hidden)]
@@ -261,7 +825,7 @@ where
     type Error = LimitError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, LimitError> {
-        Self::handle_from_request(request)
+        Self::handle_from_request(request).await
     }
 }
 
@@ -1,7 +1,7 @@
 //! Errors for governed requests which implement
 //! [Responder](rocket::response::Responder).
 
-use super::{header::Header, Quota};
+use super::{header::Header, Budget, Quota};
 use rocket::{
     response::{self, Responder},
     Request,
@@ -25,6 +25,20 @@ pub enum LimitError {
     /// in a more compliant way for its resources.
     GovernedRequest(u64, Quota),
 
+    /// Governed request of a window [Algorithm](crate::Algorithm) for the
+    /// next provided seconds (first), with the seconds until the window is
+    /// reset (second) and the `Quota` defining the limit per window.
+    GovernedWindow(u64, u64, Quota),
+
+    /// The usage [Budget] of the key is exhausted for the next provided
+    /// seconds until the budget is reset.
+    BudgetExhausted(u64, Budget),
+
+    /// Request shed by the [LoadShedder](crate::LoadShedder) because of the
+    /// load of the server. The client should retry after the provided
+    /// seconds, the `retry-after` header is omitted for 0.
+    ServiceUnavailable(u64),
+
     /// There is no remote client IP address known in the request. Might be
     /// a misconfigured server environment.
     MissingClientIpAddr,
@@ -35,13 +49,20 @@ pub enum LimitError {
     /// There is a route without name and this can not be matched for
     /// rate limiting
     MissingRouteName,
+
+    /// The path parameter of the [KeyPart](crate::KeyPart) is not in the
+    /// route of the request, so the request can't be keyed.
+    UnknownKeyParam(&'static str),
 }
 
 /// Implements [Responder] to provide
 /// [Result](rocket::response::Result) possibilities.
 impl<'r, 'o: 'r> Responder<'r, 'o> for &LimitError {
     fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
-        let mut handler = catcher::too_many_requests_handler(request);
+        let mut handler = match self {
+            LimitError::ServiceUnavailable(_) => catcher::service_unavailable_handler(request),
+            _ => catcher::too_many_requests_handler(request),
+        };
 
         match self {
             LimitError::Error => {
@@ -59,6 +80,22 @@ impl<'r, 'o: 'r> Responder<'r, 'o> for &LimitError {
                     quota.burst_size_replenished_in().as_secs(),
                 ));
             }
+            LimitError::GovernedWindow(wait_time, reset, quota) => {
+                handler.set_header(Header::RetryAfter(*wait_time));
+                handler.set_header(Header::XRateLimitLimit(quota.burst_size().get() as u64));
+                handler.set_header(Header::XRateLimitReset(*reset));
+            }
+            LimitError::BudgetExhausted(reset, budget) => {
+                handler.set_header(Header::RetryAfter(*reset));
+                handler.set_header(Header::XRateLimitBudgetLimit(budget.limit().get() as u64));
+                handler.set_header(Header::XRateLimitBudgetRemaining(0));
+                handler.set_header(Header::XRateLimitBudgetReset(*reset));
+            }
+            LimitError::ServiceUnavailable(retry_after) => {
+                if *retry_after > 0 {
+                    handler.set_header(Header::RetryAfter(*retry_after));
+                }
+            }
             LimitError::MissingClientIpAddr => {
                 handler.set_header(Header::XRateLimitError(
                     "application not retrieving client ip",
@@ -70,6 +107,9 @@ impl<'r, 'o: 'r> Responder<'r, 'o> for &LimitError {
             LimitError::MissingRouteName => {
                 handler.set_header(Header::XRateLimitError("route without name"));
             }
+            LimitError::UnknownKeyParam(_) => {
+                handler.set_header(Header::XRateLimitError("key param not in route"));
+            }
         };
 
         Ok(handler)
@@ -4,7 +4,7 @@ use rocket::{
     http::{ContentType, Status},
     Request, Response,
 };
-use std::{borrow::Cow, io::Cursor};
+use std::io::Cursor;
 
 // copied from rocket/src/catcher/catcher.rs because it is also pub(crate)
 
@@ -66,35 +66,57 @@ macro_rules! json_error_template {
 
 /// Create the handler for `Status::TooManyRequests`.
 pub(crate) fn too_many_requests_handler<'r, 'o: 'r>(req: &'r Request<'_>) -> Response<'o> {
-    let preferred = req.accept().map(|a| a.preferred());
-    let (mime, text) = if preferred.map_or(false, |a| a.is_json()) {
-        let json: Cow<'_, str> = json_error_template!(
+    handler(
+        req,
+        Status::TooManyRequests,
+        json_error_template!(
             429,
             "Too Many Requests",
             "Too many requests have been received recently."
-        )
-        .into();
-
-        (ContentType::JSON, json)
-    } else {
-        let html: Cow<'_, str> = html_error_template!(
+        ),
+        html_error_template!(
             429,
             "Too Many Requests",
             "Too many requests have been received recently."
-        )
-        .into();
+        ),
+    )
+}
 
+/// Create the handler for `Status::ServiceUnavailable`.
+pub(crate) fn service_unavailable_handler<'r, 'o: 'r>(req: &'r Request<'_>) -> Response<'o> {
+    handler(
+        req,
+        Status::ServiceUnavailable,
+        json_error_template!(
+            503,
+            "Service Unavailable",
+            "The server is temporarily unable to service your request due to high load."
+        ),
+        html_error_template!(
+            503,
+            "Service Unavailable",
+            "The server is temporarily unable to service your request due to high load."
+        ),
+    )
+}
+
+/// Create the handler responding with `status` and the `json` or `html`
+/// error text, whichever is preferred by `req`.
+fn handler<'r, 'o: 'r>(
+    req: &'r Request<'_>,
+    status: Status,
+    json: &'static str,
+    html: &'static str,
+) -> Response<'o> {
+    let preferred = req.accept().map(|a| a.preferred());
+    let (mime, text) = if preferred.map_or(false, |a| a.is_json()) {
+        (ContentType::JSON, json)
+    } else {
         (ContentType::HTML, html)
     };
 
-    let mut r = Response::build()
-        .status(Status::TooManyRequests)
-        .header(mime)
-        .finalize();
-    match text {
-        Cow::Owned(v) => r.set_sized_body(v.len(), Cursor::new(v)),
-        Cow::Borrowed(v) => r.set_sized_body(v.len(), Cursor::new(v)),
-    };
+    let mut r = Response::build().status(status).header(mime).finalize();
+    r.set_sized_body(text.len(), Cursor::new(text));
 
     r
 }
@@ -25,6 +25,10 @@ use rocket::{
 ///
 /// * [X_RATELIMIT_LIMIT](crate::header::X_RATELIMIT_LIMIT)
 /// * [X_RATELIMIT_REMAINING](crate::header::X_RATELIMIT_REMAINING)
+/// * [X_RATELIMIT_SHADOW](crate::header::X_RATELIMIT_SHADOW)
+/// * [X_RATELIMIT_BUDGET_LIMIT](crate::header::X_RATELIMIT_BUDGET_LIMIT)
+/// * [X_RATELIMIT_BUDGET_REMAINING](crate::header::X_RATELIMIT_BUDGET_REMAINING)
+/// * [X_RATELIMIT_BUDGET_RESET](crate::header::X_RATELIMIT_BUDGET_RESET)
 ///
 /// which can be used by HTTP clients to adjust service requests.
 ///
@@ -69,6 +73,16 @@ impl Fairing for LimitHeaderGen {
                 state.quota.burst_size().get().into(),
             ));
             response.set_header(Header::XRateLimitRemaining(state.request_capacity.into()));
+            if let Some(wait_time) = state.shadow_wait {
+                response.set_header(Header::XRateLimitShadow(wait_time));
+            }
+            if let Some(budget) = &state.budget {
+                response.set_header(Header::XRateLimitBudgetLimit(
+                    budget.budget().limit().get().into(),
+                ));
+                response.set_header(Header::XRateLimitBudgetRemaining(budget.remaining().into()));
+                response.set_header(Header::XRateLimitBudgetReset(budget.reset().as_secs()));
+            }
         }
     }
 }
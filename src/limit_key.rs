@@ -0,0 +1,254 @@
+//! Keys of the rate limiters.
+
+use crate::{logger::error, LimitError};
+use rocket::{
+    http::{
+        uri::{fmt::Path, Segments},
+        RawStr,
+    },
+    Request, Route,
+};
+use std::{
+    fmt::{self, Display},
+    net::{AddrParseError, IpAddr},
+    str::FromStr,
+};
+
+/// Part of the [LimitKey] of a request.
+///
+/// The parts are selected per route with
+/// [RocketGovernable::key_parts()](crate::RocketGovernable::key_parts()).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyPart {
+    /// Client IP address of the request.
+    ///
+    /// Requests without client IP address fail with
+    /// [LimitError::MissingClientIpAddr](crate::LimitError::MissingClientIpAddr).
+    ClientIp,
+    /// Path parameter of the route by name, e.g. `id` of route
+    /// `/accounts/<id>/reset`.
+    ///
+    /// A trailing parameter `<path..>` provides all remaining segments.
+    ///
+    /// Requests of a route without the parameter fail with
+    /// [LimitError::UnknownKeyParam](crate::LimitError::UnknownKeyParam).
+    Param(&'static str),
+    /// First value of the query parameter by name.
+    ///
+    /// A missing query parameter is keyed as empty value.
+    Query(&'static str),
+}
+
+/// Key of a rate limiter, which is the client IP address by default.
+///
+/// The key consists of the [KeyParts](KeyPart) selected by
+/// [RocketGovernable::key_parts()](crate::RocketGovernable::key_parts()).
+///
+/// The text representation is the client IP address (empty if not part of
+/// the key) followed by the percent-encoded parameter values, each
+/// prefixed with `/`, e.g. `192.0.2.1/42`.
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket_governor::LimitKey;
+/// use std::net::IpAddr;
+///
+/// let client: IpAddr = "192.0.2.1".parse().unwrap();
+/// let key = LimitKey::new(Some(client), ["42"]);
+/// assert_eq!("192.0.2.1/42", key.to_string());
+/// assert_eq!(key, "192.0.2.1/42".parse().unwrap());
+/// assert_eq!(LimitKey::from(client), "192.0.2.1".parse().unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LimitKey {
+    client_ip: Option<IpAddr>,
+    params: Box<[Box<str>]>,
+}
+
+impl LimitKey {
+    /// Creates the key of `client_ip` and parameter values `params`.
+    pub fn new<P, S>(client_ip: Option<IpAddr>, params: P) -> Self
+    where
+        P: IntoIterator<Item = S>,
+        S: Into<Box<str>>,
+    {
+        Self {
+            client_ip,
+            params: params.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Client IP address, if part of the key.
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
+    /// Values of the parameters in order of the [KeyParts](KeyPart).
+    pub fn params(&self) -> impl Iterator<Item = &str> {
+        self.params.iter().map(AsRef::as_ref)
+    }
+}
+
+impl From<IpAddr> for LimitKey {
+    fn from(client_ip: IpAddr) -> Self {
+        Self {
+            client_ip: Some(client_ip),
+            params: Box::default(),
+        }
+    }
+}
+
+impl From<&IpAddr> for LimitKey {
+    fn from(client_ip: &IpAddr) -> Self {
+        Self::from(*client_ip)
+    }
+}
+
+impl From<&LimitKey> for LimitKey {
+    fn from(key: &LimitKey) -> Self {
+        key.clone()
+    }
+}
+
+impl Display for LimitKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(client_ip) = &self.client_ip {
+            Display::fmt(client_ip, f)?;
+        }
+        for param in self.params.iter() {
+            write!(f, "/{}", RawStr::new(param).percent_encode())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LimitKey {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('/');
+        let client_ip = match parts.next() {
+            Some("") | None => None,
+            Some(client_ip) => Some(client_ip.parse()?),
+        };
+        let params = parts
+            .map(|param| RawStr::new(param).percent_decode_lossy().into())
+            .collect();
+
+        Ok(Self { client_ip, params })
+    }
+}
+
+/// [KeyParts](KeyPart) of a route to build the [LimitKey] of requests.
+///
+/// The path parameters are resolved per request from the matched route,
+/// because a limiter can govern routes with different paths, e.g. a route
+/// mounted at several bases.
+#[derive(Clone, Debug)]
+pub(crate) struct KeyStrategy {
+    client_ip: bool,
+    params: Box<[KeyPart]>,
+}
+
+impl KeyStrategy {
+    /// Creates the strategy of `parts`.
+    pub(crate) fn new(parts: &[KeyPart]) -> Self {
+        Self {
+            client_ip: parts.contains(&KeyPart::ClientIp),
+            params: parts
+                .iter()
+                .filter(|part| **part != KeyPart::ClientIp)
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Index of the path segment of the parameter `name` in `route` and
+    /// `true` if it is a trailing parameter.
+    fn segment_of(name: &str, route: &Route) -> Option<(usize, bool)> {
+        let segments = route.uri.path().split('/').filter(|s| !s.is_empty());
+        for (n, segment) in segments.enumerate() {
+            if let Some(param) = segment.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+                if param == name {
+                    return Some((n, false));
+                } else if param.strip_suffix("..") == Some(name) {
+                    return Some((n, true));
+                }
+            }
+        }
+        None
+    }
+
+    /// The [LimitKey] of `request` matched by `route`.
+    ///
+    /// Fails with [LimitError::MissingClientIpAddr], if the client IP address
+    /// is part of the key, but unknown, and with
+    /// [LimitError::UnknownKeyParam], if a path parameter isn't in `route`.
+    pub(crate) fn key(&self, request: &Request<'_>, route: &Route) -> Result<LimitKey, LimitError> {
+        let client_ip = if self.client_ip {
+            Some(request.client_ip().ok_or(LimitError::MissingClientIpAddr)?)
+        } else {
+            None
+        };
+        if self.params.is_empty() {
+            return Ok(LimitKey {
+                client_ip,
+                params: Box::default(),
+            });
+        }
+
+        let path = request.uri().path();
+        let params = self
+            .params
+            .iter()
+            .map(|param| match *param {
+                KeyPart::Param(name) => match Self::segment_of(name, route) {
+                    Some((n, false)) => Ok(path.segments().get(n).unwrap_or_default().into()),
+                    Some((n, true)) => Ok(Self::trailing(path.segments().skip(n))),
+                    None => {
+                        error!("key param {} not in route {}", name, route);
+                        Err(LimitError::UnknownKeyParam(name))
+                    }
+                },
+                KeyPart::Query(name) => Ok(request
+                    .query_value::<&str>(name)
+                    .and_then(Result::ok)
+                    .unwrap_or_default()
+                    .into()),
+                KeyPart::ClientIp => Ok(Box::default()),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(LimitKey { client_ip, params })
+    }
+
+    fn trailing(segments: Segments<'_, Path>) -> Box<str> {
+        segments.collect::<Vec<_>>().join("/").into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_key_string() {
+        let client_ip: IpAddr = "192.0.2.1".parse().unwrap();
+
+        for (key, s) in [
+            (LimitKey::from(client_ip), "192.0.2.1"),
+            (LimitKey::new(Some(client_ip), ["42"]), "192.0.2.1/42"),
+            (LimitKey::new(None, ["a/b", ""]), "/a%2Fb/"),
+            (
+                LimitKey::new(Some("::1".parse().unwrap()), ["x y"]),
+                "::1/x%20y",
+            ),
+        ] {
+            assert_eq!(s, key.to_string());
+            assert_eq!(key, s.parse().unwrap());
+        }
+
+        assert!("no.ip/42".parse::<LimitKey>().is_err());
+    }
+}
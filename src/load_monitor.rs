@@ -0,0 +1,148 @@
+//! Provides [`Fairing`](rocket::fairing::Fairing) in the implementation
+//! [`LoadMonitor`] which is [attachable](rocket::Rocket::attach()) to
+//! [`Rocket`](rocket::Rocket)-instance.
+
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    Data, Request, Response,
+};
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Number of requests in flight counted by the [LoadMonitor] and the
+/// [LoadShedder](crate::LoadShedder).
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// Moving average of the handler latency in nanoseconds.
+static LATENCY: AtomicU64 = AtomicU64::new(0);
+
+/// Weight of a new latency in the moving average as reciprocal.
+const LATENCY_WEIGHT: u64 = 16;
+
+/// Number of requests in flight measured by the [LoadMonitor] and the
+/// [LoadShedder](crate::LoadShedder).
+pub(crate) fn in_flight() -> usize {
+    IN_FLIGHT.load(Ordering::Relaxed)
+}
+
+/// Marks a request counted in [IN_FLIGHT], so that it is counted once by
+/// all fairings.
+struct InFlight(AtomicBool);
+
+/// Counts `request` in flight, if not counted yet.
+///
+/// Returns the number of requests in flight.
+pub(crate) fn enter(request: &Request<'_>) -> usize {
+    let _ = request.local_cache(|| {
+        let _ = IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
+        InFlight(AtomicBool::new(true))
+    });
+    in_flight()
+}
+
+/// Stops counting `request` in flight, if counted.
+pub(crate) fn leave(request: &Request<'_>) {
+    let InFlight(counted) = request.local_cache(|| InFlight(AtomicBool::new(false)));
+    if counted.swap(false, Ordering::Relaxed) {
+        let _ = IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Moving average of the handler latency measured by the [LoadMonitor].
+pub(crate) fn latency() -> Duration {
+    Duration::from_nanos(LATENCY.load(Ordering::Relaxed))
+}
+
+/// Start of a request measured by the [LoadMonitor].
+struct Started(Option<Instant>);
+
+/// Provides [`Fairing`](rocket::fairing::Fairing) implementation
+/// which is [attachable](rocket::Rocket::attach()) to
+/// [`Rocket`](rocket::Rocket)-instance.
+///
+/// `LoadMonitor` measures the requests in flight, shared with the
+/// [LoadShedder](crate::LoadShedder), and the moving average of
+/// the latency of the requests, which are the
+/// [LoadSignals](crate::LoadSignal) of the
+/// [adaptive mode](crate::RocketGovernable::adaptive()).
+///
+/// ## Example usage
+///
+/// ```rust
+/// use rocket;
+/// use rocket_governor::LoadMonitor;
+///
+/// #[rocket::launch]
+/// fn launch_rocket() -> _ {
+///     rocket::build().attach(LoadMonitor)
+/// }
+/// ```
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoadMonitor;
+
+impl LoadMonitor {
+    /// Number of requests in flight.
+    pub fn in_flight() -> usize {
+        in_flight()
+    }
+
+    /// Moving average of the latency of the requests.
+    pub fn latency() -> Duration {
+        latency()
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for LoadMonitor {
+    fn info(&self) -> Info {
+        Info {
+            name: "Load Monitor",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+        let _ = enter(request);
+        let _ = request.local_cache(|| Started(Some(Instant::now())));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, _response: &mut Response<'r>) {
+        leave(request);
+        let Started(started) = request.local_cache(|| Started(None));
+        let Some(started) = started else {
+            return;
+        };
+
+        let latency = u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        let _ = LATENCY.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| {
+            Some(if average == 0 {
+                latency
+            } else {
+                average - average / LATENCY_WEIGHT + latency / LATENCY_WEIGHT
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    #[test]
+    fn test_count_once() {
+        let client = Client::untracked(rocket::build()).expect("no rocket instance");
+        let request = client.get("/");
+
+        // counted once by both fairings
+        assert_eq!(1, enter(&request));
+        assert_eq!(1, enter(&request));
+        leave(&request);
+        assert_eq!(0, in_flight());
+        leave(&request);
+        assert_eq!(0, in_flight());
+    }
+}
@@ -0,0 +1,281 @@
+//! Provides [`Fairing`](rocket::fairing::Fairing) in the implementation
+//! [`LoadShedder`] which is [attachable](rocket::Rocket::attach()) to
+//! [`Rocket`](rocket::Rocket)-instance, with the request guard [LoadShed].
+
+use crate::{
+    load_monitor,
+    logger::{error, info},
+    LimitError, Priority,
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    DefaultDirectRateLimiter, Quota,
+};
+use rocket::{
+    async_trait, catch,
+    fairing::{Fairing, Info, Kind},
+    http::Status,
+    request::{FromRequest, Outcome},
+    Data, Request, Response,
+};
+use std::{fmt, time::Duration};
+
+/// Decision of the [LoadShedder] about a request, `None` if the fairing is
+/// not attached.
+struct Shed(Option<Result<Priority, LimitError>>);
+
+/// Provides [`Fairing`](rocket::fairing::Fairing) implementation
+/// which is [attachable](rocket::Rocket::attach()) to
+/// [`Rocket`](rocket::Rocket)-instance.
+///
+/// `LoadShedder` protects the whole server by shedding requests, when the
+/// requests in flight or the rate of all requests exceed the thresholds of
+/// the [Priority] class of a request.
+///
+/// The fairing decides about every request, but only routes with the
+/// request guard [LoadShed] reject shed requests with
+/// [`Status::ServiceUnavailable`] and the `retry-after` header.
+/// Register the catcher [rocket_governor_unavailable_catcher()] to respond
+/// with the headers.
+///
+/// Requests of [Priority::Critical] are never shed and classes without a
+/// threshold are not shed.
+///
+/// ## Example usage
+///
+/// ```rust
+/// use rocket::{catchers, get, http::Status, launch, routes, Request};
+/// use rocket_governor::{
+///     rocket_governor_unavailable_catcher, LoadShed, LoadShedder, NonZeroU32, Priority, Quota,
+/// };
+///
+/// #[get("/")]
+/// fn route_example(_shed: LoadShed) -> Status {
+///     Status::Ok
+/// }
+///
+/// fn priority(request: &Request<'_>) -> Priority {
+///     if request.uri().path().starts_with("/health") {
+///         Priority::Critical
+///     } else if request.headers().contains("Authorization") {
+///         Priority::Normal
+///     } else {
+///         Priority::Low
+///     }
+/// }
+///
+/// #[launch]
+/// fn launch_rocket() -> _ {
+///     let shedder = LoadShedder::new()
+///         .with_max_in_flight(Priority::Low, 100)
+///         .with_max_in_flight(Priority::Normal, 200)
+///         .with_max_rate(Priority::Low, Quota::per_second(NonZeroU32::new(500).unwrap()))
+///         .with_priority(priority);
+///
+///     rocket::build()
+///         .mount("/", routes![route_example])
+///         .register("/", catchers![rocket_governor_unavailable_catcher])
+///         .attach(shedder)
+/// }
+/// ```
+///
+pub struct LoadShedder {
+    /// Thresholds by [Priority] below [Priority::Critical].
+    max_in_flight: [Option<usize>; 3],
+    max_rate: [Option<DefaultDirectRateLimiter>; 3],
+    retry_after: Duration,
+    priority: fn(&Request<'_>) -> Priority,
+}
+
+impl LoadShedder {
+    /// Creates the `LoadShedder` without thresholds, with `retry-after` of
+    /// 1 second and all requests of [Priority::Normal].
+    pub fn new() -> Self {
+        Self {
+            max_in_flight: [None; 3],
+            max_rate: [None, None, None],
+            retry_after: Duration::from_secs(1),
+            priority: |_| Priority::Normal,
+        }
+    }
+
+    /// Sheds requests of `priority`, if more than `max` requests are in
+    /// flight.
+    ///
+    /// Ignored for [Priority::Critical].
+    pub fn with_max_in_flight(mut self, priority: Priority, max: usize) -> Self {
+        if let Some(max_in_flight) = self.max_in_flight.get_mut(priority as usize) {
+            *max_in_flight = Some(max);
+        }
+        self
+    }
+
+    /// Sheds requests of `priority`, if the rate of all requests exceeds
+    /// `quota`.
+    ///
+    /// Ignored for [Priority::Critical].
+    pub fn with_max_rate(mut self, priority: Priority, quota: Quota) -> Self {
+        if let Some(max_rate) = self.max_rate.get_mut(priority as usize) {
+            *max_rate = Some(DefaultDirectRateLimiter::direct(quota));
+        }
+        self
+    }
+
+    /// Time the client should wait before retrying a request shed because of
+    /// the requests in flight.
+    ///
+    /// The `retry-after` of a request shed because of the rate is the time
+    /// until the rate is below the threshold.
+    pub fn with_retry_after(self, retry_after: Duration) -> Self {
+        Self {
+            retry_after,
+            ..self
+        }
+    }
+
+    /// Determines the [Priority] of a request by `priority`.
+    ///
+    /// The request is not routed yet.
+    pub fn with_priority(self, priority: fn(&Request<'_>) -> Priority) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Number of requests in flight.
+    ///
+    /// The count is shared with the [LoadMonitor](crate::LoadMonitor).
+    pub fn in_flight(&self) -> usize {
+        load_monitor::in_flight()
+    }
+
+    /// Decides about a request of `priority` with `in_flight` requests.
+    fn decide(&self, priority: Priority, in_flight: usize) -> Result<Priority, LimitError> {
+        // every request counts for the rate of all classes
+        let mut wait = None;
+        for (class, max_rate) in self.max_rate.iter().enumerate() {
+            if let Some(Err(notuntil)) = max_rate.as_ref().map(|limiter| limiter.check()) {
+                if class == priority as usize {
+                    wait = Some(notuntil.wait_time_from(DefaultClock::default().now()));
+                }
+            }
+        }
+
+        let max_in_flight = self.max_in_flight.get(priority as usize).copied().flatten();
+        let retry_after = if max_in_flight.map_or(false, |max| in_flight > max) {
+            self.retry_after.max(wait.unwrap_or_default())
+        } else if let Some(wait) = wait {
+            wait
+        } else {
+            return Ok(priority);
+        };
+
+        Err(LimitError::ServiceUnavailable(
+            retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0),
+        ))
+    }
+}
+
+impl fmt::Debug for LoadShedder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadShedder")
+            .field("max_in_flight", &self.max_in_flight)
+            .field("retry_after", &self.retry_after)
+            .field("in_flight", &self.in_flight())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for LoadShedder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Fairing for LoadShedder {
+    fn info(&self) -> Info {
+        Info {
+            name: "Load Shedder",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+        let in_flight = load_monitor::enter(request);
+        let priority = (self.priority)(request);
+        let shed = self.decide(priority, in_flight);
+        if shed.is_err() {
+            info!(
+                "shed {} priority request {} {} with {} in flight",
+                priority.as_str(),
+                request.method(),
+                request.uri(),
+                in_flight
+            );
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                method = request.method().as_str(),
+                uri = %request.uri(),
+                priority = priority.as_str(),
+                in_flight,
+                "shed request"
+            );
+        }
+        let _ = request.local_cache(|| Shed(Some(shed)));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, _response: &mut Response<'r>) {
+        load_monitor::leave(request);
+    }
+}
+
+/// Request guard rejecting the requests shed by the [LoadShedder] with
+/// [`Status::ServiceUnavailable`].
+///
+/// Without the attached [LoadShedder] no request is shed.
+///
+/// See [LoadShedder] for an example.
+#[derive(Clone, Copy, Debug)]
+pub struct LoadShed {
+    priority: Priority,
+}
+
+impl LoadShed {
+    /// The [Priority] of the request.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for LoadShed {
+    type Error = LimitError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, LimitError> {
+        match request.local_cache(|| Shed(None)) {
+            Shed(Some(Ok(priority))) => Outcome::Success(Self {
+                priority: *priority,
+            }),
+            Shed(Some(Err(e))) => Outcome::Error((Status::ServiceUnavailable, e.clone())),
+            Shed(None) => {
+                error!("LoadShedder not attached: request: {:?}", request);
+                Outcome::Success(Self {
+                    priority: Priority::default(),
+                })
+            }
+        }
+    }
+}
+
+/// A default implementation for Rocket [Catcher] handling HTTP
+/// ServiceUnavailable responses of requests shed by the [LoadShedder].
+///
+/// See [LoadShedder] for an example.
+///
+/// [Catcher]: https://api.rocket.rs/v0.5/rocket/struct.Catcher.html
+#[catch(503)]
+pub fn rocket_governor_unavailable_catcher<'r>(request: &'r Request) -> &'r LimitError {
+    match request.local_cache(|| Shed(None)) {
+        Shed(Some(Err(e))) => e,
+        _ => &LimitError::ServiceUnavailable(0),
+    }
+}
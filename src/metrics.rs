@@ -0,0 +1,219 @@
+//! Provides counters of rate limiter decisions and the Rocket route
+//! [`rocket_governor_metrics()`] exposing them in
+//! [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+//!
+//! __Available__ only with __feature metrics__!
+
+use crate::{observer::Decision, registry::Registry};
+use lazy_static::lazy_static;
+use rocket::{
+    get,
+    http::{ContentType, Method},
+};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+/// Decision counters of a registered route.
+#[derive(Debug, Default)]
+pub(crate) struct Counters([AtomicU64; 5]);
+
+impl Counters {
+    /// Count `decision`.
+    #[inline]
+    pub(crate) fn inc(&self, decision: Decision) {
+        let _ = self.0[decision as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self, decision: Decision) -> u64 {
+        self.0[decision as usize].load(Ordering::Relaxed)
+    }
+}
+
+lazy_static! {
+    /// Errors of requests which can not be assigned to a limiter, because
+    /// of missing route information.
+    static ref UNROUTED_ERRORS: Mutex<HashMap<(&'static str, &'static str), u64>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Count an error of a request which has no registered limiter.
+pub(crate) fn inc_unrouted_error(guard: &'static str, method: Option<Method>) {
+    let method = method.map_or("", |m| m.as_str());
+    let mut errors = UNROUTED_ERRORS.lock().unwrap();
+    *errors.entry((guard, method)).or_insert(0) += 1;
+}
+
+/// Rocket route providing the metrics of all registered limiters in
+/// [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+///
+/// Metrics are
+///
+/// * `rocket_governor_decisions_total` counter of decisions labeled with
+///   `guard`, `method` and `route` of the request, `bucket` with the name of
+///   the shared bucket limiting the route, if any, and `decision`
+///   (`allowed`, `limited`, `shadowed`, `errored` or `delayed`)
+/// * `rocket_governor_keys` gauge of tracked keys (client addresses) per
+///   limiter labeled with `guard`, `method` and `route`
+/// * `rocket_governor_evicted_keys_total` counter of keys evicted because of
+///   [RocketGovernable::max_keys()](crate::RocketGovernable::max_keys())
+///   labeled with `guard`, `method` and `route`
+/// * `rocket_governor_quota_multiplier` gauge of the quota multiplier of
+///   limiters in
+///   [adaptive mode](crate::RocketGovernable::adaptive())
+///   labeled with `guard`, `method` and `route`
+///
+/// The decisions are counted per route, also of routes sharing a bucket and
+/// of [manual checks](crate::RocketGovernor::check()) of a route name.
+/// The other metrics of shared buckets are labeled with an empty `method`
+/// and the bucket name as `route`.
+///
+/// __Available__ only with __feature metrics__!
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket::{launch, routes};
+/// use rocket_governor::rocket_governor_metrics;
+///
+/// #[launch]
+/// fn launch_rocket() -> _ {
+///     rocket::build()
+///         .mount("/internal", routes![rocket_governor_metrics])
+/// }
+/// ```
+#[get("/metrics")]
+pub fn rocket_governor_metrics() -> (ContentType, String) {
+    let content_type =
+        ContentType::new("text", "plain").with_params([("version", "0.0.4"), ("charset", "utf-8")]);
+
+    (content_type, render())
+}
+
+/// Render metrics in Prometheus text format.
+fn render() -> String {
+    let mut limiters = Registry::limiters();
+    limiters.sort_by(|a, b| {
+        (a.guard, a.method_str(), &a.route_name).cmp(&(b.guard, b.method_str(), &b.route_name))
+    });
+
+    let mut out = String::new();
+
+    out.push_str(
+        "# HELP rocket_governor_decisions_total Rate limiter decisions of governed requests.\n",
+    );
+    out.push_str("# TYPE rocket_governor_decisions_total counter\n");
+    let mut routes = Registry::routes();
+    routes.sort_by(|(guard_a, method_a, a), (guard_b, method_b, b)| {
+        (guard_a, method_a.as_str(), &a.route_name).cmp(&(
+            guard_b,
+            method_b.as_str(),
+            &b.route_name,
+        ))
+    });
+    for (guard, method, route) in &routes {
+        let bucket = if route.limiter.is_bucket() {
+            escape(&route.limiter.route_name)
+        } else {
+            String::new()
+        };
+        for decision in Decision::ALL {
+            let _ = writeln!(
+                out,
+                "rocket_governor_decisions_total{{guard=\"{}\",method=\"{}\",route=\"{}\",bucket=\"{}\",decision=\"{}\"}} {}",
+                escape(guard),
+                method.as_str(),
+                escape(&route.route_name),
+                bucket,
+                decision.as_str(),
+                route.counters.get(decision)
+            );
+        }
+    }
+    {
+        let errors = UNROUTED_ERRORS.lock().unwrap();
+        let mut errors = errors.iter().collect::<Vec<_>>();
+        errors.sort();
+        for ((guard, method), count) in errors {
+            let _ = writeln!(
+                out,
+                "rocket_governor_decisions_total{{guard=\"{}\",method=\"{}\",route=\"\",bucket=\"\",decision=\"{}\"}} {}",
+                escape(guard),
+                method,
+                Decision::Errored.as_str(),
+                count
+            );
+        }
+    }
+
+    out.push_str("# HELP rocket_governor_keys Number of tracked keys per rate limiter.\n");
+    out.push_str("# TYPE rocket_governor_keys gauge\n");
+    for limiter in &limiters {
+        let _ = writeln!(
+            out,
+            "rocket_governor_keys{{guard=\"{}\",method=\"{}\",route=\"{}\"}} {}",
+            escape(limiter.guard),
+            limiter.method_str(),
+            escape(&limiter.route_name),
+            limiter.len()
+        );
+    }
+
+    out.push_str(
+        "# HELP rocket_governor_evicted_keys_total Keys evicted because of the maximum number of keys.\n",
+    );
+    out.push_str("# TYPE rocket_governor_evicted_keys_total counter\n");
+    for limiter in &limiters {
+        let _ = writeln!(
+            out,
+            "rocket_governor_evicted_keys_total{{guard=\"{}\",method=\"{}\",route=\"{}\"}} {}",
+            escape(limiter.guard),
+            limiter.method_str(),
+            escape(&limiter.route_name),
+            limiter.evicted()
+        );
+    }
+
+    out.push_str(
+        "# HELP rocket_governor_quota_multiplier Quota multiplier of adaptive rate limiters.\n",
+    );
+    out.push_str("# TYPE rocket_governor_quota_multiplier gauge\n");
+    for limiter in &limiters {
+        if let Some(multiplier) = limiter.multiplier() {
+            let _ = writeln!(
+                out,
+                "rocket_governor_quota_multiplier{{guard=\"{}\",method=\"{}\",route=\"{}\"}} {}",
+                escape(limiter.guard),
+                limiter.method_str(),
+                escape(&limiter.route_name),
+                multiplier
+            );
+        }
+    }
+
+    out
+}
+
+/// Escape a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!("route", escape("route"));
+        assert_eq!(r#"a\"b\\c\nd"#, escape("a\"b\\c\nd"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Observe the decisions of the rate limiters with a [DecisionListener].
+//!
+//! A [DecisionListener] is invoked by the [RocketGovernor](crate::RocketGovernor)
+//! guard with a [DecisionRecord] for every governed request.
+//!
+//! Listeners can be registered
+//!
+//! * per [RocketGovernable](crate::RocketGovernable) by implementing
+//!   [RocketGovernable::listener()](crate::RocketGovernable::listener())
+//! * globally for all guards with [register_listener()]
+//!
+//! Listeners are called synchronously in the request guard, so they need to
+//! return fast. Use the [ChannelListener] for asynchronous delivery of
+//! the records to a receiving task.
+
+use super::{BudgetState, LimitKey, Method, Quota};
+use lazy_static::lazy_static;
+use rocket::tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+/// Decision of the guard about a governed request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// Request is not limited.
+    Allowed,
+    /// Request is limited.
+    Limited,
+    /// Request would be limited, but the route is in shadow mode.
+    ///
+    /// See [RocketGovernable::shadow()](crate::RocketGovernable::shadow()).
+    Shadowed,
+    /// Request could not be checked, see [LimitError](crate::LimitError).
+    Errored,
+    /// Request was limited, but waited until allowed in queue mode.
+    ///
+    /// See [RocketGovernable::queue_mode()](crate::RocketGovernable::queue_mode()).
+    Delayed,
+}
+
+impl Decision {
+    #[cfg(feature = "metrics")]
+    pub(crate) const ALL: [Decision; 5] = [
+        Decision::Allowed,
+        Decision::Limited,
+        Decision::Shadowed,
+        Decision::Errored,
+        Decision::Delayed,
+    ];
+
+    /// Lowercase name of the decision.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Allowed => "allowed",
+            Decision::Limited => "limited",
+            Decision::Shadowed => "shadowed",
+            Decision::Errored => "errored",
+            Decision::Delayed => "delayed",
+        }
+    }
+}
+
+/// Structured record about the [Decision] of the guard.
+#[derive(Clone, Debug)]
+pub struct DecisionRecord {
+    pub(crate) guard: &'static str,
+    pub(crate) method: Option<Method>,
+    pub(crate) route_name: Option<Arc<str>>,
+    pub(crate) key: Option<LimitKey>,
+    pub(crate) quota: Option<Quota>,
+    pub(crate) remaining: u32,
+    pub(crate) wait_time: Duration,
+    pub(crate) budget: Option<BudgetState>,
+    pub(crate) decision: Decision,
+}
+
+impl DecisionRecord {
+    /// Type name of the [RocketGovernable](crate::RocketGovernable)
+    /// implementation of the guard.
+    pub fn guard(&self) -> &'static str {
+        self.guard
+    }
+
+    /// [Method] of the route, if the request has been routed.
+    pub fn method(&self) -> Option<Method> {
+        self.method
+    }
+
+    /// Name of the route, if the request has been routed to an identified
+    /// route.
+    ///
+    /// See [RouteIdentity](crate::RouteIdentity).
+    pub fn route_name(&self) -> Option<&str> {
+        self.route_name.as_deref()
+    }
+
+    /// The [LimitKey] of the request, which is the client IP address by
+    /// default.
+    pub fn key(&self) -> Option<&LimitKey> {
+        self.key.as_ref()
+    }
+
+    /// The [Quota] of the limiter.
+    pub fn quota(&self) -> Option<&Quota> {
+        self.quota.as_ref()
+    }
+
+    /// Number of requests which can be done, before the key is limited.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Time to wait until a request of the key would not be limited or the
+    /// time the request waited, if [delayed](Decision::Delayed).
+    ///
+    /// Zero if the request is not limited.
+    pub fn wait_time(&self) -> Duration {
+        self.wait_time
+    }
+
+    /// State of the usage [Budget](crate::Budget) of the key, if the
+    /// limiter has a budget and the request has been checked against it.
+    pub fn budget(&self) -> Option<&BudgetState> {
+        self.budget.as_ref()
+    }
+
+    /// The [Decision] about the request.
+    pub fn decision(&self) -> Decision {
+        self.decision
+    }
+}
+
+/// Listener for the [Decisions](Decision) of the guard.
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket_governor::{
+///     observer::{Decision, DecisionListener, DecisionRecord},
+///     Method, Quota, RocketGovernable,
+/// };
+///
+/// pub struct AuditListener;
+///
+/// impl DecisionListener for AuditListener {
+///     fn on_decision(&self, record: &DecisionRecord) {
+///         if record.decision() == Decision::Limited {
+///             println!("limited {:?} on {:?}", record.key(), record.route_name());
+///         }
+///     }
+/// }
+///
+/// static AUDIT: AuditListener = AuditListener;
+///
+/// pub struct RateLimitGuard;
+///
+/// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+///     fn quota(_method: Method, _route_name: &str) -> Quota {
+///         Quota::per_second(Self::nonzero(1u32))
+///     }
+///
+///     fn listener() -> Option<&'static dyn DecisionListener> {
+///         Some(&AUDIT)
+///     }
+/// }
+/// ```
+pub trait DecisionListener: Send + Sync {
+    /// Called with the [DecisionRecord] of every governed request.
+    ///
+    /// This is called in the request guard and so it should not block.
+    fn on_decision(&self, record: &DecisionRecord);
+}
+
+/// [DecisionListener] delivering the [DecisionRecords](DecisionRecord) to a
+/// bounded channel.
+///
+/// The records can be received asynchronously with the [Receiver] returned
+/// by [ChannelListener::new()].
+/// If the channel is full, the record is dropped instead of blocking the
+/// request. The number of dropped records is provided by
+/// [ChannelListener::dropped()].
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket::{fairing::AdHoc, launch, tokio};
+/// use rocket_governor::observer::{register_listener, ChannelListener};
+/// use std::sync::Arc;
+///
+/// #[launch]
+/// fn launch_rocket() -> _ {
+///     rocket::build().attach(AdHoc::on_liftoff("Audit", |_| {
+///         Box::pin(async {
+///             let (listener, mut receiver) = ChannelListener::new(1024);
+///             register_listener(Arc::new(listener));
+///             tokio::spawn(async move {
+///                 while let Some(record) = receiver.recv().await {
+///                     println!("{:?}", record);
+///                 }
+///             });
+///         })
+///     }))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ChannelListener {
+    dropped: AtomicU64,
+    sender: Sender<DecisionRecord>,
+}
+
+impl ChannelListener {
+    /// Creates the listener with a channel of `capacity` records and returns
+    /// it together with the [Receiver] of the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> (Self, Receiver<DecisionRecord>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (
+            Self {
+                dropped: AtomicU64::new(0),
+                sender,
+            },
+            receiver,
+        )
+    }
+
+    /// Number of records dropped, because the channel has been full or closed.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl DecisionListener for ChannelListener {
+    fn on_decision(&self, record: &DecisionRecord) {
+        match self.sender.try_send(record.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) | Err(TrySendError::Closed(_)) => {
+                let _ = self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+lazy_static! {
+    static ref LISTENERS: RwLock<Vec<Arc<dyn DecisionListener>>> = RwLock::new(Vec::new());
+}
+
+/// `true` if there is any listener in [LISTENERS] to skip the lock.
+static HAS_LISTENERS: AtomicBool = AtomicBool::new(false);
+
+/// Registers a [DecisionListener] for the decisions of all guards.
+pub fn register_listener(listener: Arc<dyn DecisionListener>) {
+    let mut listeners = LISTENERS.write().unwrap();
+    listeners.push(listener);
+    HAS_LISTENERS.store(true, Ordering::Release);
+}
+
+/// Notify the `listener` of the guard and all registered listeners.
+pub(crate) fn notify(listener: Option<&dyn DecisionListener>, record: &DecisionRecord) {
+    if let Some(listener) = listener {
+        listener.on_decision(record);
+    }
+
+    if HAS_LISTENERS.load(Ordering::Acquire) {
+        let listeners = LISTENERS.read().unwrap();
+        for listener in listeners.iter() {
+            listener.on_decision(record);
+        }
+    }
+}
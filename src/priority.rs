@@ -0,0 +1,38 @@
+//! Priority classes of requests.
+
+/// Priority class of a request.
+///
+/// See [LoadShedder::with_priority()](crate::LoadShedder::with_priority()) and
+/// [RocketGovernable::priority()](crate::RocketGovernable::priority()).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Requests shed first, e.g. anonymous or batch requests.
+    Low,
+    /// Default priority of requests.
+    #[default]
+    Normal,
+    /// Requests shed last, e.g. of paying customers.
+    High,
+    /// Requests which are never shed, e.g. health checks and admin routes.
+    Critical,
+}
+
+impl Priority {
+    /// All priority classes from low to critical.
+    pub const ALL: [Priority; 4] = [
+        Priority::Low,
+        Priority::Normal,
+        Priority::High,
+        Priority::Critical,
+    ];
+
+    /// Lowercase name of the priority.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Critical => "critical",
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Queue-and-wait mode of the guard.
+
+use std::{num::NonZeroUsize, time::Duration};
+
+/// Queue mode of a route, in which a rate limited request waits until the
+/// limiter allows it instead of being rejected immediately.
+///
+/// A request is only rejected with
+/// [LimitError::GovernedRequest](crate::LimitError::GovernedRequest), if the
+/// projected wait would exceed the [maximum delay](QueueMode::max_delay()) or
+/// if there are already [max_waiting](QueueMode::max_waiting()) requests of
+/// the key waiting.
+/// The projected wait includes one replenish interval of the
+/// [Quota](crate::Quota) per request of the key waiting ahead and the full
+/// [jitter](QueueMode::jitter()), so the request is rejected immediately
+/// instead of after waiting in vain.
+///
+/// See [RocketGovernable::queue_mode()](crate::RocketGovernable::queue_mode()).
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket_governor::{NonZeroUsize, QueueMode};
+/// use std::time::Duration;
+///
+/// let queue_mode = QueueMode::new(Duration::from_secs(2))
+///     .with_jitter(Duration::from_millis(50))
+///     .with_max_waiting(NonZeroUsize::new(4).unwrap());
+/// assert_eq!(Duration::from_secs(2), queue_mode.max_delay());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QueueMode {
+    max_delay: Duration,
+    jitter: Duration,
+    max_waiting: NonZeroUsize,
+}
+
+impl QueueMode {
+    /// Default maximum number of waiting requests per key.
+    pub const DEFAULT_MAX_WAITING: usize = 10;
+
+    /// Creates the queue mode with maximum delay `max_delay` of a request,
+    /// no jitter and [DEFAULT_MAX_WAITING](Self::DEFAULT_MAX_WAITING).
+    pub fn new(max_delay: Duration) -> Self {
+        Self {
+            max_delay,
+            jitter: Duration::ZERO,
+            max_waiting: NonZeroUsize::new(Self::DEFAULT_MAX_WAITING).unwrap(),
+        }
+    }
+
+    /// Adds a random delay of up to `jitter` to the wait of a request, so
+    /// waiting requests don't retry all at once.
+    ///
+    /// The [maximum delay](QueueMode::max_delay()) includes the jitter.
+    pub fn with_jitter(self, jitter: Duration) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Limits the number of waiting requests per key to `max_waiting`.
+    ///
+    /// Further requests of the key are rejected immediately.
+    pub fn with_max_waiting(self, max_waiting: NonZeroUsize) -> Self {
+        Self {
+            max_waiting,
+            ..self
+        }
+    }
+
+    /// Maximum delay of a request.
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Maximum random delay added to the wait of a request.
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Maximum number of waiting requests per key.
+    pub fn max_waiting(&self) -> NonZeroUsize {
+        self.max_waiting
+    }
+}
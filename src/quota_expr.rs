@@ -0,0 +1,377 @@
+//! Human-readable quota expressions like `100/min burst 20`.
+
+use crate::{NonZeroU32, Quota};
+use grammar::{ParseError, QuotaParts, UNITS};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+    time::Duration,
+};
+
+mod grammar;
+
+/// Quota expression of `count` requests per `period` with a burst size,
+/// e.g. `100/min burst 20` or `1/2s`.
+///
+/// The expression is parsed with [FromStr] and written with [Display], so
+/// it can be used in config files, macros and admin APIs alike. With the
+/// feature __serde__ it is (de)serialized as string.
+///
+/// The format is `<count>/[<amount>]<unit>[ burst <burst>]` with the units
+/// `ms`, `s`, `min`, `h` and `d` (also `us`, `ns` and long forms like
+/// `minute` or `hours`). The burst size defaults to `count`.
+///
+/// The [Quota] replenishes one request every `period / count`, like
+/// [Quota::per_minute()] does for a minute.
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket_governor::{NonZeroU32, Quota, QuotaExpr};
+/// use std::time::Duration;
+///
+/// let expr: QuotaExpr = "100/min burst 20".parse().unwrap();
+/// assert_eq!(100, expr.count().get());
+/// assert_eq!(Duration::from_secs(60), expr.period());
+/// assert_eq!(20, expr.burst().get());
+/// assert_eq!("100/min burst 20", expr.to_string());
+///
+/// let quota = Quota::per_minute(NonZeroU32::new(100).unwrap())
+///     .allow_burst(NonZeroU32::new(20).unwrap());
+/// assert_eq!(quota, expr.quota());
+///
+/// let expr: QuotaExpr = "1/2s".parse().unwrap();
+/// assert_eq!(Duration::from_secs(2), expr.quota().replenish_interval());
+///
+/// let err = "10/fortnight".parse::<QuotaExpr>().unwrap_err();
+/// assert_eq!("invalid unit `fortnight` of the period", err.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuotaExpr {
+    count: NonZeroU32,
+    period: Duration,
+    burst: NonZeroU32,
+}
+
+impl QuotaExpr {
+    /// Creates the expression of `count` requests per `period` with a burst
+    /// size of `count`.
+    ///
+    /// Fails with [QuotaExprError::PeriodTooShort], if `period` is shorter
+    /// than 1 nanosecond per request.
+    pub fn new(count: NonZeroU32, period: Duration) -> Result<Self, QuotaExprError> {
+        if period < Duration::from_nanos(u64::from(count.get())) {
+            return Err(QuotaExprError::PeriodTooShort);
+        }
+        Ok(Self {
+            count,
+            period,
+            burst: count,
+        })
+    }
+
+    /// Sets the `burst` size.
+    pub fn with_burst(self, burst: NonZeroU32) -> Self {
+        Self { burst, ..self }
+    }
+
+    /// Expression of the quotas of the macros of the feature __derive__,
+    /// which are parsed by the same grammar at compile time.
+    ///
+    /// The macros evaluate it in a constant, so that it can't fail at
+    /// runtime.
+    #[cfg(feature = "derive")]
+    #[doc(hidden)]
+    pub const fn __validated(count: u32, period: u64, burst: u32) -> Self {
+        let (Some(count), Some(burst)) = (NonZeroU32::new(count), NonZeroU32::new(burst)) else {
+            panic!("count and burst of a quota need to be at least 1");
+        };
+        assert!(
+            period >= count.get() as u64,
+            "period of a quota needs to be at least 1 nanosecond per request"
+        );
+        Self {
+            count,
+            period: Duration::from_nanos(period),
+            burst,
+        }
+    }
+
+    /// Number of requests per [period](QuotaExpr::period()).
+    pub fn count(&self) -> NonZeroU32 {
+        self.count
+    }
+
+    /// Period of the [count](QuotaExpr::count()) of requests.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Burst size of the [Quota].
+    pub fn burst(&self) -> NonZeroU32 {
+        self.burst
+    }
+
+    /// The [Quota] of the expression.
+    pub fn quota(&self) -> Quota {
+        // the period is at least 1 nanosecond per request
+        Quota::with_period(self.period / self.count.get())
+            .unwrap_or_else(|| Quota::per_second(self.count))
+            .allow_burst(self.burst)
+    }
+}
+
+impl From<QuotaExpr> for Quota {
+    fn from(expr: QuotaExpr) -> Self {
+        expr.quota()
+    }
+}
+
+/// The expression of `quota` with the burst size as count.
+///
+/// The period is rounded to the largest unit within the rounding error of
+/// the replenish interval, so e.g. [Quota::per_second()] of 3 requests is
+/// `3/s`.
+impl From<Quota> for QuotaExpr {
+    fn from(quota: Quota) -> Self {
+        let burst = quota.burst_size();
+        let nanos = quota
+            .replenish_interval()
+            .as_nanos()
+            .saturating_mul(u128::from(burst.get()));
+        let nanos = UNITS
+            .iter()
+            .map(|(_, _, unit)| {
+                let unit = u128::from(*unit);
+                (nanos + unit - 1) / unit * unit
+            })
+            .find(|rounded| rounded - nanos < u128::from(burst.get()))
+            .unwrap_or(nanos);
+        Self {
+            count: burst,
+            period: Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX)),
+            burst,
+        }
+    }
+}
+
+impl Display for QuotaExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.period.as_nanos();
+        let (unit, length) = UNITS
+            .iter()
+            .map(|(unit, _, length)| (*unit, u128::from(*length)))
+            .find(|(_, length)| nanos % length == 0)
+            .unwrap_or(("ns", 1));
+        write!(f, "{}/", self.count)?;
+        if nanos != length {
+            write!(f, "{}", nanos / length)?;
+        }
+        f.write_str(unit)?;
+        if self.burst != self.count {
+            write!(f, " burst {}", self.burst)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for QuotaExpr {
+    type Err = QuotaExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let QuotaParts {
+            count,
+            period,
+            burst,
+        } = QuotaParts::parse(s)?;
+        Ok(Self {
+            count,
+            period: Duration::from_nanos(period),
+            burst: burst.unwrap_or(count),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for QuotaExpr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for QuotaExpr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = QuotaExpr;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a quota expression like \"100/min burst 20\"")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+/// Error parsing a [QuotaExpr].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuotaExprError {
+    /// The expression is empty.
+    Empty,
+    /// The expression is not of the form `<count>/<period>`.
+    Syntax(String),
+    /// The count of requests is no number of at least 1.
+    InvalidCount(String),
+    /// The amount of the period is no number of at least 1 or too large.
+    InvalidPeriod(String),
+    /// The unit of the period is unknown.
+    InvalidUnit(String),
+    /// The burst size is no number of at least 1.
+    InvalidBurst(String),
+    /// The period is shorter than 1 nanosecond per request.
+    PeriodTooShort,
+}
+
+impl From<ParseError<'_>> for QuotaExprError {
+    fn from(err: ParseError<'_>) -> Self {
+        match err {
+            ParseError::Empty => QuotaExprError::Empty,
+            ParseError::Syntax(s) => QuotaExprError::Syntax(s.to_string()),
+            ParseError::InvalidCount(s) => QuotaExprError::InvalidCount(s.to_string()),
+            ParseError::InvalidPeriod(s) => QuotaExprError::InvalidPeriod(s.to_string()),
+            ParseError::InvalidUnit(s) => QuotaExprError::InvalidUnit(s.to_string()),
+            ParseError::InvalidBurst(s) => QuotaExprError::InvalidBurst(s.to_string()),
+            ParseError::PeriodTooShort => QuotaExprError::PeriodTooShort,
+        }
+    }
+}
+
+impl Display for QuotaExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err = match self {
+            QuotaExprError::Empty => ParseError::Empty,
+            QuotaExprError::Syntax(s) => ParseError::Syntax(s),
+            QuotaExprError::InvalidCount(s) => ParseError::InvalidCount(s),
+            QuotaExprError::InvalidPeriod(s) => ParseError::InvalidPeriod(s),
+            QuotaExprError::InvalidUnit(s) => ParseError::InvalidUnit(s),
+            QuotaExprError::InvalidBurst(s) => ParseError::InvalidBurst(s),
+            QuotaExprError::PeriodTooShort => ParseError::PeriodTooShort,
+        };
+        err.fmt(f)
+    }
+}
+
+impl Error for QuotaExprError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuotaExpr, QuotaExprError};
+    use crate::{NonZeroU32, Quota};
+    use std::time::Duration;
+
+    fn nonzero(n: u32) -> NonZeroU32 {
+        NonZeroU32::new(n).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let expr: QuotaExpr = "100/min burst 20".parse().unwrap();
+        assert_eq!(
+            (nonzero(100), Duration::from_secs(60), nonzero(20)),
+            (expr.count(), expr.period(), expr.burst())
+        );
+
+        let expr: QuotaExpr = " 1 / 2 seconds ".parse().unwrap();
+        assert_eq!(
+            (nonzero(1), Duration::from_secs(2), nonzero(1)),
+            (expr.count(), expr.period(), expr.burst())
+        );
+
+        let expr: QuotaExpr = "5/250ms".parse().unwrap();
+        assert_eq!(Duration::from_millis(50), expr.quota().replenish_interval());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = |s: &str| s.parse::<QuotaExpr>().unwrap_err();
+
+        assert_eq!(QuotaExprError::Empty, err(" "));
+        assert_eq!(QuotaExprError::Syntax("10".to_string()), err("10"));
+        assert_eq!(QuotaExprError::InvalidCount("0".to_string()), err("0/s"));
+        assert_eq!(QuotaExprError::InvalidPeriod("0s".to_string()), err("1/0s"));
+        assert_eq!(QuotaExprError::InvalidUnit("y".to_string()), err("1/y"));
+        assert_eq!(QuotaExprError::InvalidUnit(String::new()), err("1/2"));
+        assert_eq!(
+            QuotaExprError::InvalidBurst("x".to_string()),
+            err("1/s burst x")
+        );
+        assert_eq!(QuotaExprError::PeriodTooShort, err("2/ns"));
+    }
+
+    #[test]
+    fn test_grammar_of_derive() {
+        // the derive crate is published on its own with a copy of the grammar
+        assert_eq!(
+            include_str!("quota_expr/grammar.rs"),
+            include_str!("../rocket-governor-derive/src/quota/grammar.rs"),
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [
+            "100/min burst 20",
+            "1/2s",
+            "3/s",
+            "10/90s",
+            "1/d",
+            "7/1500us",
+        ] {
+            assert_eq!(s, s.parse::<QuotaExpr>().unwrap().to_string());
+        }
+        assert_eq!("1/2min", "1/120s".parse::<QuotaExpr>().unwrap().to_string());
+        assert_eq!(
+            "5/h",
+            "5/60 minutes".parse::<QuotaExpr>().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_from_quota() {
+        let expr = QuotaExpr::from(Quota::per_second(nonzero(3)));
+        assert_eq!("3/s", expr.to_string());
+        assert_eq!(Quota::per_second(nonzero(3)), expr.quota());
+
+        let quota = Quota::per_minute(nonzero(30)).allow_burst(nonzero(10));
+        assert_eq!("10/20s", QuotaExpr::from(quota).to_string());
+        assert_eq!(quota, QuotaExpr::from(quota).quota());
+
+        let quota = Quota::with_period(Duration::from_nanos(1_000_000_007)).unwrap();
+        assert_eq!("1/1000000007ns", QuotaExpr::from(quota).to_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() {
+        use serde::{
+            de::{value::Error, IntoDeserializer},
+            Deserialize,
+        };
+
+        let de = IntoDeserializer::<Error>::into_deserializer("100/min burst 20");
+        let expr = QuotaExpr::deserialize(de).unwrap();
+        assert_eq!(nonzero(20), expr.burst());
+
+        let de = IntoDeserializer::<Error>::into_deserializer("100/fortnight");
+        let err = QuotaExpr::deserialize(de).unwrap_err();
+        assert_eq!("invalid unit `fortnight` of the period", err.to_string());
+    }
+}
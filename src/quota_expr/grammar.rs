@@ -0,0 +1,141 @@
+//! Grammar of quota expressions like `100/min burst 20`.
+//!
+//! The crates `rocket-governor` and `rocket-governor-derive` have identical
+//! copies of the module, so that the macros validate quotas by the same
+//! grammar at compile time as `QuotaExpr` at runtime. The copies are
+//! compared by a test of `rocket-governor`, and only depend on `std`.
+
+use std::{
+    fmt::{self, Display},
+    num::NonZeroU32,
+};
+
+/// Length of a second in nanoseconds.
+pub(crate) const SECOND: u64 = 1_000_000_000;
+/// Length of a minute in nanoseconds.
+pub(crate) const MINUTE: u64 = 60 * SECOND;
+/// Length of an hour in nanoseconds.
+pub(crate) const HOUR: u64 = 60 * MINUTE;
+/// Length of a day in nanoseconds.
+const DAY: u64 = 24 * HOUR;
+
+/// Units of the period with their aliases and length in nanoseconds,
+/// largest first.
+pub(crate) const UNITS: [(&str, &[&str], u64); 7] = [
+    ("d", &["day", "days"], DAY),
+    ("h", &["hour", "hours"], HOUR),
+    ("min", &["m", "minute", "minutes"], MINUTE),
+    ("s", &["sec", "second", "seconds"], SECOND),
+    ("ms", &[], 1_000_000),
+    ("us", &[], 1_000),
+    ("ns", &[], 1),
+];
+
+/// Parts of a quota expression of `count` requests per `period` in
+/// nanoseconds with an optional `burst` size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct QuotaParts {
+    pub(crate) count: NonZeroU32,
+    pub(crate) period: u64,
+    pub(crate) burst: Option<NonZeroU32>,
+}
+
+impl QuotaParts {
+    /// Parts of `count` requests per `period` in nanoseconds.
+    ///
+    /// Fails if `period` is shorter than 1 nanosecond per request.
+    pub(crate) fn new(count: NonZeroU32, period: u64) -> Result<Self, ParseError<'static>> {
+        if period < u64::from(count.get()) {
+            return Err(ParseError::PeriodTooShort);
+        }
+        Ok(Self {
+            count,
+            period,
+            burst: None,
+        })
+    }
+
+    /// Parses `<count>/[<amount>]<unit>[ burst <burst>]`.
+    pub(crate) fn parse(s: &str) -> Result<Self, ParseError<'_>> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let (count, rest) = s.split_once('/').ok_or(ParseError::Syntax(s))?;
+        let (period, burst) = match rest.split_once("burst") {
+            Some((period, burst)) => (period, Some(burst.trim())),
+            None => (rest, None),
+        };
+
+        let count = count.trim();
+        let count = count
+            .parse::<NonZeroU32>()
+            .map_err(|_| ParseError::InvalidCount(count))?;
+
+        let period = period.trim();
+        let split = period
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(period.len());
+        let (amount, unit) = period.split_at(split);
+        let amount = match amount {
+            "" => 1,
+            amount => amount
+                .parse::<u64>()
+                .ok()
+                .filter(|amount| *amount > 0)
+                .ok_or(ParseError::InvalidPeriod(period))?,
+        };
+        let unit = unit.trim();
+        let length = UNITS
+            .iter()
+            .find(|(name, aliases, _)| *name == unit || aliases.contains(&unit))
+            .map(|(_, _, length)| *length)
+            .ok_or(ParseError::InvalidUnit(unit))?;
+        let period = amount
+            .checked_mul(length)
+            .ok_or(ParseError::InvalidPeriod(period))?;
+
+        let parts = Self::new(count, period)?;
+        match burst {
+            Some(burst) => burst
+                .parse::<NonZeroU32>()
+                .map(|burst| Self {
+                    burst: Some(burst),
+                    ..parts
+                })
+                .map_err(|_| ParseError::InvalidBurst(burst)),
+            None => Ok(parts),
+        }
+    }
+}
+
+/// Error parsing a quota expression with the invalid part of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ParseError<'a> {
+    Empty,
+    Syntax(&'a str),
+    InvalidCount(&'a str),
+    InvalidPeriod(&'a str),
+    InvalidUnit(&'a str),
+    InvalidBurst(&'a str),
+    PeriodTooShort,
+}
+
+impl Display for ParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => f.write_str("empty quota expression"),
+            ParseError::Syntax(s) => write!(
+                f,
+                "invalid quota expression `{s}`, expected e.g. `100/min burst 20`"
+            ),
+            ParseError::InvalidCount(s) => write!(f, "invalid count of requests `{s}`"),
+            ParseError::InvalidPeriod(s) => write!(f, "invalid period `{s}`"),
+            ParseError::InvalidUnit(s) => write!(f, "invalid unit `{s}` of the period"),
+            ParseError::InvalidBurst(s) => write!(f, "invalid burst size `{s}`"),
+            ParseError::PeriodTooShort => {
+                f.write_str("period shorter than 1 nanosecond per request")
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Schedules of quotas by time of day and weekday.
+
+use governor::Quota;
+use rocket::time::{OffsetDateTime, UtcOffset, Weekday};
+
+/// Quota of a [QuotaSchedule] from the `start` to the `end` hour of the
+/// `weekdays`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    weekdays: Vec<Weekday>,
+    start: u8,
+    end: u8,
+    quota: Quota,
+}
+
+impl Entry {
+    /// Returns `true` if the entry is in effect at the local time `now`.
+    fn contains(&self, now: &OffsetDateTime) -> bool {
+        let hour = now.hour();
+        let (start, end) = (self.start, self.end);
+        if start < end {
+            (start..end).contains(&hour) && self.weekdays.contains(&now.weekday())
+        } else {
+            // hours past midnight belong to the entry of the previous day
+            (hour >= start && self.weekdays.contains(&now.weekday()))
+                || (hour < end && self.weekdays.contains(&now.weekday().previous()))
+        }
+    }
+}
+
+/// Schedule of quotas replacing the
+/// [quota()](crate::RocketGovernable::quota()) of a limiter at hours of
+/// weekdays, e.g. 10 requests per second on weekdays from 08:00 to 20:00
+/// and the quota of the limiter otherwise.
+///
+/// The first entry matching the time is in effect. The hours are in the
+/// time zone of the fixed [UtcOffset] of the schedule (default UTC).
+///
+/// The time is provided by the clock of the schedule, which can be replaced
+/// by [with_clock()](Self::with_clock()) for testing.
+///
+/// See [RocketGovernable::quota_schedule()](crate::RocketGovernable::quota_schedule()).
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket::time::{Date, Month, PrimitiveDateTime, Time, Weekday};
+/// use rocket_governor::{NonZeroU32, Quota, QuotaSchedule};
+///
+/// let day_quota = Quota::per_second(NonZeroU32::new(10).unwrap());
+/// let schedule = QuotaSchedule::new().with_quota(&QuotaSchedule::WEEKDAYS, 8, 20, day_quota);
+///
+/// // Monday
+/// let date = Date::from_calendar_date(2023, Month::January, 2).unwrap();
+/// assert_eq!(Weekday::Monday, date.weekday());
+/// let at = |hour| PrimitiveDateTime::new(date, Time::from_hms(hour, 0, 0).unwrap()).assume_utc();
+///
+/// assert_eq!(Some(day_quota), schedule.quota_at(at(8)));
+/// assert_eq!(None, schedule.quota_at(at(20)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuotaSchedule {
+    entries: Vec<Entry>,
+    offset: UtcOffset,
+    clock: fn() -> OffsetDateTime,
+}
+
+impl QuotaSchedule {
+    /// Monday to Friday.
+    pub const WEEKDAYS: [Weekday; 5] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+    ];
+
+    /// Saturday and Sunday.
+    pub const WEEKEND: [Weekday; 2] = [Weekday::Saturday, Weekday::Sunday];
+
+    /// All days of the week.
+    pub const EVERY_DAY: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Creates an empty schedule, which keeps the quota of the limiter.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            offset: UtcOffset::UTC,
+            clock: OffsetDateTime::now_utc,
+        }
+    }
+
+    /// Adds the `quota` in effect on the `weekdays` from the `start` hour
+    /// until the `end` hour (exclusive).
+    ///
+    /// If `start` is not before `end`, the hours last past midnight into the
+    /// next day, e.g. `20, 8` from 20:00 to 08:00.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `start` is after 23 or `end` after 24.
+    pub fn with_quota(mut self, weekdays: &[Weekday], start: u8, end: u8, quota: Quota) -> Self {
+        assert!(
+            start < 24 && end <= 24,
+            "hours out of range: {}..{}",
+            start,
+            end
+        );
+        self.entries.push(Entry {
+            weekdays: weekdays.to_vec(),
+            start,
+            end,
+            quota,
+        });
+        self
+    }
+
+    /// Aligns the hours to the time zone of `offset`.
+    pub fn with_offset(self, offset: UtcOffset) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// Replaces the clock providing the current time of the schedule.
+    pub fn with_clock(self, clock: fn() -> OffsetDateTime) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Offset of the time zone of the hours.
+    pub fn offset(&self) -> UtcOffset {
+        self.offset
+    }
+
+    /// The current time of the clock of the schedule.
+    pub fn now(&self) -> OffsetDateTime {
+        (self.clock)()
+    }
+
+    /// The scheduled [Quota] at `now` or `None` if the quota of the limiter
+    /// is in effect.
+    pub fn quota_at(&self, now: OffsetDateTime) -> Option<Quota> {
+        self.entry_at(now).map(|(_, quota)| quota)
+    }
+
+    /// The scheduled quotas in order of the entries.
+    pub(crate) fn quotas(&self) -> impl Iterator<Item = Quota> + '_ {
+        self.entries.iter().map(|entry| entry.quota)
+    }
+
+    /// Index and [Quota] of the entry in effect at `now`.
+    pub(crate) fn entry_at(&self, now: OffsetDateTime) -> Option<(usize, Quota)> {
+        let now = now.to_offset(self.offset);
+        self.entries
+            .iter()
+            .enumerate()
+            .find(|(_, entry)| entry.contains(&now))
+            .map(|(index, entry)| (index, entry.quota))
+    }
+}
+
+impl Default for QuotaSchedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QuotaSchedule;
+    use governor::Quota;
+    use rocket::time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+    use std::num::NonZeroU32;
+
+    /// 2023-01-02 is a Monday.
+    fn at(day: u8, hour: u8) -> OffsetDateTime {
+        let date = Date::from_calendar_date(2023, Month::January, day).unwrap();
+        PrimitiveDateTime::new(date, Time::from_hms(hour, 30, 0).unwrap()).assume_utc()
+    }
+
+    fn quota(n: u32) -> Quota {
+        Quota::per_second(NonZeroU32::new(n).unwrap())
+    }
+
+    #[test]
+    fn test_quota_at() {
+        let schedule = QuotaSchedule::new()
+            .with_quota(&QuotaSchedule::WEEKDAYS, 8, 20, quota(10))
+            .with_quota(&QuotaSchedule::EVERY_DAY, 0, 24, quota(50));
+
+        assert_eq!(Some(quota(50)), schedule.quota_at(at(2, 7)));
+        assert_eq!(Some(quota(10)), schedule.quota_at(at(2, 8)));
+        assert_eq!(Some(quota(10)), schedule.quota_at(at(6, 19)));
+        assert_eq!(Some(quota(50)), schedule.quota_at(at(6, 20)));
+        // Saturday
+        assert_eq!(Some(quota(50)), schedule.quota_at(at(7, 12)));
+    }
+
+    #[test]
+    fn test_quota_at_past_midnight() {
+        // Friday night
+        let schedule =
+            QuotaSchedule::new().with_quota(&[rocket::time::Weekday::Friday], 22, 6, quota(1));
+
+        assert_eq!(None, schedule.quota_at(at(6, 21)));
+        assert_eq!(Some(quota(1)), schedule.quota_at(at(6, 22)));
+        assert_eq!(Some(quota(1)), schedule.quota_at(at(7, 5)));
+        assert_eq!(None, schedule.quota_at(at(7, 6)));
+        assert_eq!(None, schedule.quota_at(at(7, 22)));
+        // Friday early morning belongs to Thursday night
+        assert_eq!(None, schedule.quota_at(at(6, 5)));
+    }
+
+    #[test]
+    fn test_quota_at_offset() {
+        let schedule = QuotaSchedule::new()
+            .with_quota(&QuotaSchedule::WEEKDAYS, 8, 20, quota(10))
+            .with_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+
+        // 06:30 UTC is 08:30 +2
+        assert_eq!(Some(quota(10)), schedule.quota_at(at(2, 6)));
+        assert_eq!(None, schedule.quota_at(at(2, 18)));
+    }
+}
@@ -0,0 +1,133 @@
+//! Provides the non-failing guard [RateLimitStatus].
+
+use crate::{
+    observer::{Decision, DecisionRecord},
+    LimitError, Quota, RocketGovernable, RocketGovernor,
+};
+use rocket::{
+    async_trait,
+    request::{FromRequest, Outcome},
+    Request,
+};
+use std::{convert::Infallible, marker::PhantomData, time::Duration};
+
+/// Request guard like [RocketGovernor], which always succeeds and provides
+/// the status of the rate limit to the handler.
+///
+/// The request is checked and consumed like with [RocketGovernor], but it is
+/// up to the handler to reject a [limited](RateLimitStatus::is_limited())
+/// request or to respond in a degraded way.
+///
+/// The handler can return the [error](RateLimitStatus::error()), which is a
+/// [Responder](rocket::response::Responder), or
+/// [`Status::TooManyRequests`](rocket::http::Status::TooManyRequests) to be
+/// handled by the registered
+/// [`rocket_governor_catcher()`](crate::rocket_governor_catcher()).
+///
+/// Don't use [RocketGovernor] and [RateLimitStatus] of the same
+/// [RocketGovernable] together in a route, because each would consume a
+/// request.
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket::{get, http::Status};
+/// use rocket_governor::{Method, Quota, RateLimitStatus, RocketGovernable};
+///
+/// pub struct RateLimitGuard;
+///
+/// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+///     fn quota(_method: Method, _route_name: &str) -> Quota {
+///         Quota::per_second(Self::nonzero(1u32))
+///     }
+/// }
+///
+/// #[get("/")]
+/// fn route_example(status: RateLimitStatus<RateLimitGuard>) -> (Status, &'static str) {
+///     if status.is_limited() {
+///         (Status::Ok, "cached response")
+///     } else {
+///         (Status::Ok, "fresh response")
+///     }
+/// }
+/// ```
+pub struct RateLimitStatus<'r, T>
+where
+    T: RocketGovernable<'r>,
+{
+    result: Result<(), LimitError>,
+    record: DecisionRecord,
+    _phantom: PhantomData<&'r T>,
+}
+
+impl<'r, T> RateLimitStatus<'r, T>
+where
+    T: RocketGovernable<'r>,
+{
+    /// The [Decision] about the request.
+    pub fn decision(&self) -> Decision {
+        self.record.decision
+    }
+
+    /// Returns `true` if the request is [limited](Decision::Limited).
+    pub fn is_limited(&self) -> bool {
+        self.record.decision == Decision::Limited
+    }
+
+    /// Number of requests which can be done, before the key is limited.
+    pub fn remaining(&self) -> u32 {
+        self.record.remaining
+    }
+
+    /// The [Quota] of the limiter, if the request could be checked.
+    pub fn quota(&self) -> Option<&Quota> {
+        self.record.quota.as_ref()
+    }
+
+    /// Time to wait until a request would not be limited.
+    ///
+    /// Zero if the request is not limited.
+    pub fn wait_time(&self) -> Duration {
+        self.record.wait_time
+    }
+
+    /// The [LimitError] which [RocketGovernor] would fail with, if the
+    /// request is [limited](Decision::Limited) or
+    /// [errored](Decision::Errored).
+    pub fn error(&self) -> Option<&LimitError> {
+        self.result.as_ref().err()
+    }
+
+    /// The [DecisionRecord] of the request.
+    pub fn record(&self) -> &DecisionRecord {
+        &self.record
+    }
+}
+
+/// Cached result of the check of the request.
+struct CachedStatus(Result<(), LimitError>, DecisionRecord);
+
+#[async_trait]
+impl<'r, T> FromRequest<'r> for RateLimitStatus<'r, T>
+where
+    T: RocketGovernable<'r>,
+{
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Infallible> {
+        let CachedStatus(result, record) = request
+            .local_cache_async(async {
+                let (result, record) = RocketGovernor::<T>::check_request(request).await;
+                // for rocket_governor_catcher() if the handler responds with 429
+                let _ = request.local_cache(|| result.clone());
+                CachedStatus(result, record)
+            })
+            .await;
+
+        Outcome::Success(Self {
+            result: result.clone(),
+            record: record.clone(),
+            _phantom: PhantomData,
+        })
+    }
+}
@@ -1,92 +1,1041 @@
-use crate::logger::debug;
+//! Module for the [Registry] of the rate limiters.
+
+use crate::logger::{debug, info};
+#[cfg(feature = "metrics")]
+use crate::metrics::Counters;
+use crate::{
+    key_sweeper,
+    limit_key::{KeyStrategy, LimitKey},
+    Adaptive, Algorithm, Budget, BudgetState, Priority, QuotaSchedule, CLOCK,
+};
+use arc_swap::ArcSwap;
+use budget_store::BudgetStore;
 use governor::{
-    clock::DefaultClock, middleware::StateInformationMiddleware,
-    state::keyed::DefaultKeyedStateStore, Quota, RateLimiter,
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
+    state::keyed::ShrinkableKeyedStateStore,
+    Jitter, NotUntil, Quota, RateLimiter,
 };
 use lazy_static::lazy_static;
-use rocket::http::Method;
+use multiplier::Multiplier;
+use rocket::{http::Method, tokio::time::sleep};
 use std::{
     any::type_name,
     collections::HashMap,
-    net::IpAddr,
-    sync::{Arc, RwLock},
+    num::{NonZeroU32, NonZeroUsize},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
 };
+use store::KeyStore;
+use waiters::{Waiter, Waiters};
+use window::WindowLimiter;
+
+mod budget_store;
+mod multiplier;
+mod shards;
+mod store;
+mod waiters;
+mod window;
+
+/// Minimum number of new keys of a limiter between removals of its idle
+/// keys without a [KeySweeper](crate::KeySweeper).
+const MIN_SWEEP_KEYS: u64 = 1_000;
+
+type KeyedRateLimiter =
+    RateLimiter<LimitKey, KeyStore<LimitKey>, DefaultClock, StateInformationMiddleware>;
+
+pub(crate) type RegisteredRateLimiter = Arc<Limiter>;
 
-pub(crate) type RegisteredRateLimiter = Arc<
-    RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock, StateInformationMiddleware>,
->;
+/// A route registered with the [Limiter] governing it.
+#[derive(Clone, Debug)]
+pub(crate) struct RegisteredRoute {
+    /// Name of the route, which differs from the name of the limiter if
+    /// the limiter is a shared bucket.
+    pub(crate) route_name: Arc<str>,
+    pub(crate) limiter: RegisteredRateLimiter,
+    /// Strategy to build the keys of requests, `None` if registered by a
+    /// [manual check](crate::RocketGovernor::check()).
+    pub(crate) key_strategy: Option<Arc<KeyStrategy>>,
+    /// Decisions about requests of the route, which are counted per route
+    /// also if the limiter is a shared bucket.
+    #[cfg(feature = "metrics")]
+    pub(crate) counters: Arc<Counters>,
+}
 
+/// Configuration of a new [Limiter] provided by the
+/// [RocketGovernable](crate::RocketGovernable) implementation.
 #[derive(Debug)]
-pub(crate) struct Registry {
-    limiter: RwLock<HashMap<Method, HashMap<String, RegisteredRateLimiter>>>,
+pub(crate) struct LimiterConfig {
+    pub(crate) quota: Quota,
+    pub(crate) max_keys: Option<NonZeroUsize>,
+    pub(crate) bucket: Option<&'static str>,
+    pub(crate) algorithm: Algorithm,
+    pub(crate) budget: Option<Budget>,
+    pub(crate) schedule: Option<QuotaSchedule>,
+    pub(crate) adaptive: Option<Adaptive>,
+    /// Capacity reserved for each [Priority].
+    pub(crate) reserved: [u32; 4],
+    pub(crate) key_strategy: Option<KeyStrategy>,
 }
 
-impl Registry {
-    pub(crate) fn get_or_insert<T>(
-        method: Method,
+/// A request allowed and consumed by a [Limiter].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Allowed {
+    pub(crate) quota: Quota,
+    /// Number of requests which can be done, before the key is limited.
+    pub(crate) remaining: u32,
+    pub(crate) budget: Option<BudgetState>,
+}
+
+/// A request denied by a [Limiter].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Denied {
+    pub(crate) quota: Quota,
+    pub(crate) wait_time: Duration,
+    /// Time until the window is reset, `None` for [Algorithm::Gcra].
+    pub(crate) reset: Option<Duration>,
+    /// State of the budget, if the budget is exhausted.
+    pub(crate) budget: Option<BudgetState>,
+}
+
+impl Denied {
+    fn of(notuntil: &NotUntil<<DefaultClock as Clock>::Instant>) -> Self {
+        Self {
+            quota: notuntil.quota(),
+            wait_time: notuntil.wait_time_from(CLOCK.now()),
+            reset: None,
+            budget: None,
+        }
+    }
+
+    fn of_budget(quota: Quota, budget: BudgetState) -> Self {
+        Self {
+            quota,
+            wait_time: budget.reset,
+            reset: Some(budget.reset),
+            budget: Some(budget),
+        }
+    }
+}
+
+/// Implementation of the [Algorithm] of a [Limiter].
+#[derive(Debug)]
+enum Backend {
+    /// The rate limiters of the quota and of the scheduled quotas, each
+    /// for every level of the multiplier and for every [Priority] with
+    /// reserved capacity, share the state of the keys.
+    Gcra {
+        rate_limiters: Vec<KeyedRateLimiter>,
+        /// Rate limiters of the same quotas on a
+        /// [peeking](KeyStore::peeking()) store.
+        peek_limiters: Vec<KeyedRateLimiter>,
+        store: KeyStore<LimitKey>,
+    },
+    Window(WindowLimiter),
+}
+
+/// Registered routes by type name of the
+/// [RocketGovernable](crate::RocketGovernable) implementation, [Method] and
+/// route name.
+type RouteMap = HashMap<(&'static str, Method), HashMap<Box<str>, RegisteredRoute>>;
+
+/// Shared bucket limiters by type name of the
+/// [RocketGovernable](crate::RocketGovernable) implementation and bucket name.
+type BucketMap = HashMap<(&'static str, &'static str), RegisteredRateLimiter>;
+
+/// A rate limiter registered for a combination of
+/// [RocketGovernable](crate::RocketGovernable) implementation, [Method] and
+/// route name or for a shared bucket of routes.
+///
+/// See [RocketGovernable::bucket()](crate::RocketGovernable::bucket()).
+///
+/// The registered limiters are provided by the [Registry].
+#[derive(Debug)]
+pub struct Limiter {
+    /// Type name of the [RocketGovernable](crate::RocketGovernable) implementation.
+    pub(crate) guard: &'static str,
+    /// `None` for a shared bucket.
+    pub(crate) method: Option<Method>,
+    /// Name of the route or of the shared bucket.
+    pub(crate) route_name: Arc<str>,
+    pub(crate) quota: Quota,
+    algorithm: Algorithm,
+    schedule: Option<QuotaSchedule>,
+    multiplier: Option<Multiplier>,
+    /// Capacity reserved for the classes above each [Priority].
+    reserved: [u32; 4],
+    /// Number of priority classes with separate rate limiters.
+    classes: usize,
+    backend: Backend,
+    budget: Option<BudgetStore>,
+    waiters: Waiters,
+    /// Number of [inserted](Self::inserted()) keys at which the idle keys
+    /// are removed without a [KeySweeper](crate::KeySweeper).
+    sweep_at: AtomicU64,
+}
+
+/// State of a key in a [Limiter].
+///
+/// See [Limiter::peek_key()].
+#[derive(Clone, Debug)]
+pub struct KeyState {
+    pub(crate) remaining: u32,
+    pub(crate) wait_time: Duration,
+    pub(crate) next_allowed: Instant,
+    pub(crate) budget: Option<BudgetState>,
+}
+
+impl KeyState {
+    /// Number of requests which can be done, before the key is limited.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Time to wait until a request of the key would not be limited.
+    ///
+    /// Zero if the key is not limited.
+    pub fn wait_time(&self) -> Duration {
+        self.wait_time
+    }
+
+    /// Earliest point in time a request of the key would not be limited.
+    pub fn next_allowed(&self) -> Instant {
+        self.next_allowed
+    }
+
+    /// State of the [Budget] of the key, if the limiter has a budget.
+    ///
+    /// The rate limit state doesn't include the budget.
+    pub fn budget(&self) -> Option<&BudgetState> {
+        self.budget.as_ref()
+    }
+}
+
+impl Limiter {
+    fn new(
+        guard: &'static str,
+        method: Option<Method>,
         route_name: &str,
-        quota: Quota,
-    ) -> RegisteredRateLimiter {
-        let route_name = type_name::<T>().to_string() + "::" + route_name;
-
-        // check if exist with readlock
-        let limiter = if let Ok(rlock) = REG.limiter.read() {
-            if let Some(meth_found) = rlock.get(&method) {
-                if let Some(limiter) = meth_found.get(&route_name) {
-                    debug!("limiter found method {} route {}", &method, &route_name);
-                    return Arc::clone(limiter);
+        config: &LimiterConfig,
+    ) -> Self {
+        let LimiterConfig {
+            quota,
+            max_keys,
+            algorithm,
+            budget,
+            adaptive,
+            reserved,
+            ..
+        } = *config;
+        let multiplier = adaptive.map(Multiplier::new);
+        let reserved = Priority::ALL.map(|priority| {
+            reserved[priority as usize + 1..]
+                .iter()
+                .fold(0u32, |sum, reserved| sum.saturating_add(*reserved))
+        });
+        let classes = if reserved[0] > 0 {
+            Priority::ALL.len()
+        } else {
+            1
+        };
+        let backend = match algorithm {
+            Algorithm::Gcra => {
+                let store = KeyStore::new(max_keys);
+                let clock = DefaultClock::default();
+                let quotas = config.schedule.iter().flat_map(QuotaSchedule::quotas);
+                let multipliers = multiplier.as_ref().map_or(vec![1.0], |multiplier| {
+                    (0..multiplier.levels())
+                        .map(|level| multiplier.at(level))
+                        .collect()
+                });
+                let quotas = std::iter::once(quota)
+                    .chain(quotas)
+                    .flat_map(|quota| {
+                        multipliers
+                            .iter()
+                            .map(move |multiplier| multiplier::scale(quota, *multiplier))
+                    })
+                    .flat_map(|quota| {
+                        reserved[..classes]
+                            .iter()
+                            .map(move |reserved| without_reserved(quota, *reserved))
+                    })
+                    .collect::<Vec<_>>();
+                let peeking = store.peeking();
+                Backend::Gcra {
+                    rate_limiters: quotas
+                        .iter()
+                        .map(|quota| KeyedRateLimiter::new(*quota, store.clone(), &clock))
+                        .collect(),
+                    peek_limiters: quotas
+                        .iter()
+                        .map(|quota| KeyedRateLimiter::new(*quota, peeking.clone(), &clock))
+                        .collect(),
+                    store,
                 }
             }
-            None
-        } else {
-            None
+            Algorithm::FixedWindow => Backend::Window(WindowLimiter::new(false, max_keys)),
+            Algorithm::SlidingWindow => Backend::Window(WindowLimiter::new(true, max_keys)),
         };
+        Self {
+            guard,
+            method,
+            route_name: route_name.into(),
+            quota,
+            algorithm,
+            schedule: config.schedule.clone(),
+            multiplier,
+            reserved,
+            classes,
+            backend,
+            budget: budget.map(|budget| BudgetStore::new(budget, max_keys)),
+            waiters: Waiters::default(),
+            sweep_at: AtomicU64::new(MIN_SWEEP_KEYS),
+        }
+    }
 
-        // get the existing limiter or create the new one with writelock and return the created
-        let limiter = if let Some(limiter) = limiter {
-            limiter
+    /// Type name of the [RocketGovernable](crate::RocketGovernable)
+    /// implementation.
+    pub fn guard(&self) -> &'static str {
+        self.guard
+    }
+
+    /// [Method] of the governed route.
+    ///
+    /// `None` if the limiter is a shared [bucket](Self::is_bucket()) of
+    /// routes.
+    pub fn method(&self) -> Option<Method> {
+        self.method
+    }
+
+    /// Name of the governed route or of the shared [bucket](Self::is_bucket()).
+    ///
+    /// See [RouteIdentity](crate::RouteIdentity).
+    pub fn route_name(&self) -> &str {
+        &self.route_name
+    }
+
+    /// Name of the [Method] or empty for a shared bucket.
+    #[cfg(any(feature = "admin", feature = "metrics"))]
+    pub(crate) fn method_str(&self) -> &'static str {
+        self.method.map_or("", Method::as_str)
+    }
+
+    /// Returns `true` if the limiter is a shared bucket of routes.
+    ///
+    /// See [RocketGovernable::bucket()](crate::RocketGovernable::bucket()).
+    pub fn is_bucket(&self) -> bool {
+        self.method.is_none()
+    }
+
+    /// The [Quota] of the limiter.
+    pub fn quota(&self) -> &Quota {
+        &self.quota
+    }
+
+    /// The [Quota] in effect now, which is the scheduled quota or the
+    /// [quota()](Self::quota()) of the limiter scaled by the
+    /// [multiplier](Self::multiplier()).
+    ///
+    /// See [RocketGovernable::quota_schedule()](crate::RocketGovernable::quota_schedule()).
+    pub fn current_quota(&self) -> Quota {
+        self.effective(Priority::Critical).1
+    }
+
+    /// The current multiplier of the quota in
+    /// [adaptive mode](crate::RocketGovernable::adaptive()).
+    ///
+    /// `None` if the limiter isn't adaptive.
+    pub fn multiplier(&self) -> Option<f64> {
+        self.multiplier
+            .as_ref()
+            .map(|multiplier| multiplier.at(multiplier.level()))
+    }
+
+    /// The [Adaptive] mode of the limiter.
+    pub fn adaptive(&self) -> Option<&Adaptive> {
+        self.multiplier.as_ref().map(Multiplier::adaptive)
+    }
+
+    /// The [QuotaSchedule] of the limiter.
+    pub fn quota_schedule(&self) -> Option<&QuotaSchedule> {
+        self.schedule.as_ref()
+    }
+
+    /// Capacity of the [Quota] reserved for the classes above `priority`.
+    ///
+    /// See [RocketGovernable::reserved_capacity()](crate::RocketGovernable::reserved_capacity()).
+    pub fn reserved_above(&self, priority: Priority) -> u32 {
+        self.reserved[priority as usize]
+    }
+
+    /// Index of the rate limiter, the [Quota] in effect now and the capacity
+    /// reserved for the classes above `priority`.
+    #[inline]
+    fn effective(&self, priority: Priority) -> (usize, Quota, u32) {
+        let (index, quota) = self
+            .schedule
+            .as_ref()
+            .and_then(|schedule| schedule.entry_at(schedule.now()))
+            .map_or((0, self.quota), |(index, quota)| (index + 1, quota));
+        let (index, quota) = match &self.multiplier {
+            Some(multiplier) => {
+                let level = multiplier.level();
+                (
+                    index * multiplier.levels() + level,
+                    multiplier::scale(quota, multiplier.at(level)),
+                )
+            }
+            None => (index, quota),
+        };
+        let class = if self.classes > 1 {
+            priority as usize
         } else {
-            let mut wlock_meth_map = REG.limiter.write().unwrap();
-            if let Some(meth_found) = wlock_meth_map.get_mut(&method) {
-                if let Some(limiter) = meth_found.get(&route_name) {
-                    debug!("limiter found method {} route {}", &method, &route_name);
-                    Arc::clone(limiter)
-                } else {
-                    debug!("new limiter method {} route {}", &method, &route_name);
-                    let limiter = Arc::new(
-                        RateLimiter::keyed(quota).with_middleware::<StateInformationMiddleware>(),
-                    );
-                    meth_found.insert(route_name, Arc::clone(&limiter));
-                    limiter
+            0
+        };
+        (
+            index * self.classes + class,
+            quota,
+            self.reserved[priority as usize],
+        )
+    }
+
+    /// Adapts the [multiplier](Self::multiplier()) to the load.
+    #[inline]
+    fn adapt(&self) {
+        let Some((previous, multiplier)) = self.multiplier.as_ref().and_then(Multiplier::adapt)
+        else {
+            return;
+        };
+        info!(
+            "adapted multiplier of limiter method {:?} route {}::{} from {} to {}",
+            &self.method, self.guard, &self.route_name, previous, multiplier
+        );
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            guard = self.guard,
+            method = self.method.map(Method::as_str),
+            route = &*self.route_name,
+            previous,
+            multiplier,
+            "adapted quota multiplier"
+        );
+        #[cfg(not(any(feature = "logger", feature = "tracing")))]
+        let _ = (previous, multiplier); // unused warning
+    }
+
+    /// The [Algorithm] of the limiter.
+    ///
+    /// See [RocketGovernable::algorithm()](crate::RocketGovernable::algorithm()).
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The [Budget] of the limiter.
+    ///
+    /// See [RocketGovernable::budget()](crate::RocketGovernable::budget()).
+    pub fn budget(&self) -> Option<&Budget> {
+        self.budget.as_ref().map(BudgetStore::budget)
+    }
+
+    /// Maximum number of tracked keys.
+    ///
+    /// See [RocketGovernable::max_keys()](crate::RocketGovernable::max_keys()).
+    pub fn max_keys(&self) -> Option<NonZeroUsize> {
+        match &self.backend {
+            Backend::Gcra { store, .. } => store.max_keys(),
+            Backend::Window(window) => window.max_keys(),
+        }
+    }
+
+    /// Number of keys evicted, because the limiter or its budget tracked
+    /// [max_keys()](Self::max_keys()).
+    pub fn evicted(&self) -> u64 {
+        let evicted = match &self.backend {
+            Backend::Gcra { store, .. } => store.evicted(),
+            Backend::Window(window) => window.evicted(),
+        };
+        evicted + self.budget.as_ref().map_or(0, BudgetStore::evicted)
+    }
+
+    /// Removes the idle keys, if no [KeySweeper](crate::KeySweeper) is
+    /// running and there have been as many new keys since the last removal
+    /// as keys remained, but at least [MIN_SWEEP_KEYS].
+    ///
+    /// The cost of the removal is linear in the number of keys, so it is
+    /// constant per new key amortized. Checks of tracked keys only read the
+    /// counters.
+    #[inline]
+    fn sweep_unswept(&self) {
+        if key_sweeper::is_running() {
+            return;
+        }
+        let sweep_at = self.sweep_at.load(Ordering::Relaxed);
+        if self.inserted() < sweep_at
+            || self
+                .sweep_at
+                .compare_exchange(sweep_at, u64::MAX, Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+
+        #[allow(unused_variables)] // only used in debug
+        let removed = self.retain_recent();
+        let remained = u64::try_from(self.len()).unwrap_or(u64::MAX);
+        self.sweep_at.store(
+            self.inserted().saturating_add(remained.max(MIN_SWEEP_KEYS)),
+            Ordering::Relaxed,
+        );
+        debug!(
+            "removed {} idle keys of limiter method {:?} route {}::{} without key sweeper",
+            removed, &self.method, self.guard, &self.route_name
+        );
+    }
+
+    /// Number of keys inserted into the limiter and its budget.
+    fn inserted(&self) -> u64 {
+        let inserted = match &self.backend {
+            Backend::Gcra { store, .. } => store.inserted(),
+            Backend::Window(window) => window.inserted(),
+        };
+        inserted + self.budget.as_ref().map_or(0, BudgetStore::inserted)
+    }
+
+    /// Check and consume one request of `key` of `priority` without logging
+    /// evictions.
+    #[inline]
+    fn check_key_by_backend(&self, key: &LimitKey, priority: Priority) -> Result<Allowed, Denied> {
+        let (index, quota, reserved) = self.effective(priority);
+        match &self.backend {
+            Backend::Gcra { rate_limiters, .. } => rate_limiters[index]
+                .check_key(key)
+                .map(|state| Allowed {
+                    quota,
+                    remaining: state.remaining_burst_capacity(),
+                    budget: None,
+                })
+                .map_err(|notuntil| Denied {
+                    quota,
+                    ..Denied::of(&notuntil)
+                }),
+            Backend::Window(window) => window.check_key(key, quota, reserved),
+        }
+    }
+
+    /// Check and consume one request of `key` of `priority` and of its
+    /// budget without logging evictions.
+    #[inline]
+    fn check_key_and_budget(&self, key: &LimitKey, priority: Priority) -> Result<Allowed, Denied> {
+        let Some(budget) = &self.budget else {
+            return self.check_key_by_backend(key, priority);
+        };
+
+        let state = budget.peek(key);
+        if state.remaining == 0 {
+            return Err(Denied::of_budget(self.current_quota(), state));
+        }
+        let allowed = self.check_key_by_backend(key, priority)?;
+        self.consume_budget(budget, key, allowed)
+    }
+
+    /// Consumes one request of the budget of `key`, which has been `allowed`
+    /// by the rate limiter.
+    fn consume_budget(
+        &self,
+        budget: &BudgetStore,
+        key: &LimitKey,
+        allowed: Allowed,
+    ) -> Result<Allowed, Denied> {
+        match budget.consume(key) {
+            Ok(state) => Ok(Allowed {
+                budget: Some(state),
+                ..allowed
+            }),
+            Err(state) => Err(Denied::of_budget(allowed.quota, state)),
+        }
+    }
+
+    /// Check and consume one request of `key` of `priority` and of its
+    /// budget.
+    #[inline]
+    pub(crate) fn check_key(&self, key: &LimitKey, priority: Priority) -> Result<Allowed, Denied> {
+        self.adapt();
+        self.sweep_unswept();
+
+        if self.max_keys().is_none() {
+            return self.check_key_and_budget(key, priority);
+        }
+
+        let evicted = self.evicted();
+        let res = self.check_key_and_budget(key, priority);
+        let evicted = self.evicted() - evicted;
+        if evicted > 0 {
+            info!(
+                "evicted {} keys of limiter method {:?} route {}::{}",
+                evicted, &self.method, self.guard, &self.route_name
+            );
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                guard = self.guard,
+                method = self.method.map(Method::as_str),
+                route = &*self.route_name,
+                evicted,
+                "max keys reached, evicted keys"
+            );
+        }
+        res
+    }
+
+    /// Enters a waiting request of `key`, if there are less than
+    /// `max_waiting` requests of `key` waiting.
+    #[inline]
+    pub(crate) fn enter_queue(
+        &self,
+        key: &LimitKey,
+        max_waiting: NonZeroUsize,
+    ) -> Option<Waiter<'_>> {
+        self.waiters.enter(key, max_waiting)
+    }
+
+    /// Waits until a request of `key` of `priority` is allowed by the rate
+    /// limiter with an additional random delay of up to `jitter` and
+    /// consumes it and one request of the budget.
+    pub(crate) async fn until_key_ready(
+        &self,
+        key: &LimitKey,
+        priority: Priority,
+        jitter: Duration,
+    ) -> Result<Allowed, Denied> {
+        let (index, quota, reserved) = self.effective(priority);
+        let allowed = match &self.backend {
+            Backend::Gcra { rate_limiters, .. } => {
+                let state = rate_limiters[index]
+                    .until_key_ready_with_jitter(key, Jitter::up_to(jitter))
+                    .await;
+                Allowed {
+                    quota,
+                    remaining: state.remaining_burst_capacity(),
+                    budget: None,
                 }
-            } else {
-                debug!("new limiter method {} route {}", &method, &route_name);
-                let mut lim_map = HashMap::new();
-                let limiter = Arc::new(
-                    RateLimiter::keyed(quota).with_middleware::<StateInformationMiddleware>(),
-                );
-                lim_map.insert(route_name, Arc::clone(&limiter));
-                wlock_meth_map.insert(method, lim_map);
-                limiter
             }
+            Backend::Window(window) => loop {
+                match window.check_key(key, quota, reserved) {
+                    Ok(allowed) => break allowed,
+                    Err(denied) => sleep(Jitter::up_to(jitter) + denied.wait_time).await,
+                }
+            },
+        };
+
+        match &self.budget {
+            Some(budget) => self.consume_budget(budget, key, allowed),
+            None => Ok(allowed),
+        }
+    }
+
+    /// Number of requests waiting in
+    /// [queue mode](crate::RocketGovernable::queue_mode()).
+    pub fn waiting(&self) -> usize {
+        self.waiters.len()
+    }
+
+    /// State of `key` without consuming a request.
+    ///
+    /// The `key` is a [LimitKey] or the client [IpAddr](std::net::IpAddr),
+    /// if the key
+    /// consists only of the client IP address.
+    ///
+    /// The state includes the capacity reserved for higher
+    /// [priority classes](crate::RocketGovernable::reserved_capacity()).
+    pub fn peek_key(&self, key: impl Into<LimitKey>) -> KeyState {
+        let key = key.into();
+        let now = CLOCK.now();
+        let (index, quota, reserved) = self.effective(Priority::Critical);
+        let res = match &self.backend {
+            Backend::Gcra { peek_limiters, .. } => peek_limiters[index]
+                .check_key(&key)
+                .map(|state| state.remaining_burst_capacity() + 1)
+                .map_err(|notuntil| Denied::of(&notuntil)),
+            Backend::Window(window) => window.peek_key(&key, quota, reserved),
+        };
+        let budget = self.budget.as_ref().map(|budget| budget.peek(&key));
+        match res {
+            Ok(remaining) => KeyState {
+                remaining,
+                wait_time: Duration::ZERO,
+                next_allowed: now,
+                budget,
+            },
+            Err(denied) => KeyState {
+                remaining: 0,
+                wait_time: denied.wait_time,
+                next_allowed: now + denied.wait_time,
+                budget,
+            },
+        }
+    }
+
+    /// Resets the state of `key`, so that it has the full [Quota] and
+    /// [Budget] again.
+    ///
+    /// Returns `true` if the key has been tracked.
+    pub fn reset_key(&self, key: impl Into<LimitKey>) -> bool {
+        let key = key.into();
+        let budget_removed = self
+            .budget
+            .as_ref()
+            .map_or(false, |budget| budget.remove(&key));
+        let removed = match &self.backend {
+            Backend::Gcra { store, .. } => store.remove(&key),
+            Backend::Window(window) => window.remove(&key),
+        };
+        removed || budget_removed
+    }
+
+    /// Resets the state of all keys.
+    pub fn clear(&self) {
+        if let Some(budget) = &self.budget {
+            budget.clear();
+        }
+        match &self.backend {
+            Backend::Gcra { store, .. } => store.clear(),
+            Backend::Window(window) => window.clear(),
+        }
+    }
+
+    /// Removes the idle keys, which have the full [Quota] again, and shrinks
+    /// the capacity of the key store.
+    ///
+    /// The budget usage of passed periods is removed as well.
+    ///
+    /// Returns the number of removed keys of the limiter and of the budget.
+    pub fn retain_recent(&self) -> usize {
+        let removed = match &self.backend {
+            Backend::Gcra { rate_limiters, .. } => {
+                let rate_limiter = &rate_limiters[self.effective(Priority::Critical).0];
+                let len = rate_limiter.len();
+                rate_limiter.retain_recent();
+                rate_limiter.shrink_to_fit();
+                len.saturating_sub(rate_limiter.len())
+            }
+            Backend::Window(window) => window.retain_recent(self.current_quota()),
         };
+        removed + self.budget.as_ref().map_or(0, BudgetStore::retain_recent)
+    }
 
-        // simple cleanup
-        limiter.retain_recent();
-        limiter.shrink_to_fit();
+    /// Number of keys tracked by the limiter.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.backend {
+            Backend::Gcra { store, .. } => store.len(),
+            Backend::Window(window) => window.len(),
+        }
+    }
 
-        limiter
+    /// Returns `true` if the limiter doesn't track any key.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        match &self.backend {
+            Backend::Gcra { store, .. } => store.is_empty(),
+            Backend::Window(window) => window.is_empty(),
+        }
     }
 }
 
-impl Default for Registry {
-    fn default() -> Self {
-        Self {
-            limiter: RwLock::new(HashMap::new()),
+/// The `quota` without the capacity `reserved` for higher priority classes,
+/// keeping the replenish interval and a burst size of at least 1.
+fn without_reserved(quota: Quota, reserved: u32) -> Quota {
+    if reserved == 0 {
+        return quota;
+    }
+    let burst = quota.burst_size().get().saturating_sub(reserved);
+    match (
+        Quota::with_period(quota.replenish_interval()),
+        NonZeroU32::new(burst.max(1)),
+    ) {
+        (Some(reduced), Some(burst)) => reduced.allow_burst(burst),
+        _ => quota,
+    }
+}
+
+/// The [Registry] of the rate limiters.
+///
+/// A [Limiter] is registered on the first request to a route governed by
+/// a [RocketGovernor](crate::RocketGovernor) guard.
+/// There is one [Limiter] per [RocketGovernable](crate::RocketGovernable)
+/// implementation, [Method] and route name or per shared bucket.
+///
+/// ## Example
+///
+/// ```rust
+/// use rocket_governor::{Method, Quota, Registry, RocketGovernable};
+/// use std::net::IpAddr;
+///
+/// pub struct RateLimitGuard;
+///
+/// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+///     fn quota(_method: Method, _route_name: &str) -> Quota {
+///         Quota::per_second(Self::nonzero(1u32))
+///     }
+/// }
+///
+/// let client: IpAddr = "192.0.2.1".parse().unwrap();
+///
+/// for limiter in Registry::limiters() {
+///     println!(
+///         "{} {:?} {}: {} keys",
+///         limiter.guard(),
+///         limiter.method(),
+///         limiter.route_name(),
+///         limiter.len()
+///     );
+/// }
+///
+/// if let Some(limiter) = Registry::get::<RateLimitGuard>(Method::Get, "route_example") {
+///     let state = limiter.peek_key(client);
+///     if state.remaining() == 0 {
+///         limiter.reset_key(client);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Registry {
+    routes: ArcSwap<RouteMap>,
+    /// Shared bucket limiters. The lock also serializes the copy-on-write
+    /// insertion of new routes.
+    buckets: Mutex<BucketMap>,
+}
+
+impl Registry {
+    /// Returns the registered route or registers it with a new limiter or
+    /// the shared bucket limiter by the [LimiterConfig] returned by `config`.
+    ///
+    /// If `keyed` is `true`, a route registered without key strategy gets
+    /// the key strategy of `config`.
+    ///
+    /// Looking up a registered route doesn't allocate and doesn't lock.
+    pub(crate) fn get_or_insert<T>(
+        method: Method,
+        route_name: &str,
+        keyed: bool,
+        config: impl FnOnce() -> LimiterConfig,
+    ) -> RegisteredRoute {
+        let guard = type_name::<T>();
+
+        if let Some(route) = Self::lookup(&REG.routes.load(), guard, method, route_name)
+            .filter(|route| !keyed || route.key_strategy.is_some())
+        {
+            debug!(
+                "limiter found method {} route {}::{}",
+                &method, guard, route_name
+            );
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                guard,
+                method = method.as_str(),
+                route = route_name,
+                keys = route.limiter.len(),
+                "limiter found"
+            );
+            return route.clone();
+        }
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "rocket_governor::registry",
+            guard,
+            method = method.as_str(),
+            route = route_name,
+        )
+        .entered();
+
+        // the hooks of the implementation and the new limiter may panic, so
+        // they run before the registry is locked
+        let config = config();
+        let bucket = config.bucket;
+        let new_route = Self::new_route(guard, method, route_name, config);
+
+        let mut buckets = Self::buckets();
+        let routes = REG.routes.load_full();
+        let found = Self::lookup(&routes, guard, method, route_name);
+        if let Some(route) = found.filter(|route| !keyed || route.key_strategy.is_some()) {
+            debug!(
+                "limiter found method {} route {}::{}",
+                &method, guard, route_name
+            );
+            return route.clone();
         }
+
+        let route = if let Some(route) = found {
+            debug!(
+                "key strategy for method {} route {}::{}",
+                &method, guard, route_name
+            );
+            RegisteredRoute {
+                key_strategy: new_route.key_strategy,
+                ..route.clone()
+            }
+        } else if let Some(bucket) = bucket {
+            // the first registered limiter of the bucket is shared
+            let limiter = buckets.entry((guard, bucket)).or_insert(new_route.limiter);
+            debug!(
+                "limiter bucket {} for method {} route {}::{}",
+                bucket, &method, guard, route_name
+            );
+            RegisteredRoute {
+                limiter: Arc::clone(limiter),
+                ..new_route
+            }
+        } else {
+            new_route
+        };
+
+        // copy-on-write, the lookups keep using the previous map until stored
+        let mut routes = RouteMap::clone(&routes);
+        let _ = routes
+            .entry((guard, method))
+            .or_default()
+            .insert(route_name.into(), route.clone());
+        REG.routes.store(Arc::new(routes));
+
+        route
+    }
+
+    /// Locks the shared bucket limiters, which are consistent also after a
+    /// panic while locked, so a poisoned lock is recovered.
+    fn buckets() -> MutexGuard<'static, BucketMap> {
+        REG.buckets.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Creates the route with a new limiter or with the limiter of the
+    /// shared bucket, if already registered.
+    fn new_route(
+        guard: &'static str,
+        method: Method,
+        route_name: &str,
+        config: LimiterConfig,
+    ) -> RegisteredRoute {
+        let registered = config
+            .bucket
+            .and_then(|bucket| Self::buckets().get(&(guard, bucket)).map(Arc::clone));
+        let limiter = if let Some(limiter) = registered {
+            limiter
+        } else if let Some(bucket) = config.bucket {
+            debug!("new limiter bucket {}::{}", guard, bucket);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                bucket,
+                burst = config.quota.burst_size().get(),
+                replenish_ms = config.quota.replenish_interval().as_millis() as u64,
+                max_keys = config.max_keys.map(NonZeroUsize::get),
+                algorithm = ?config.algorithm,
+                "new limiter"
+            );
+            Arc::new(Limiter::new(guard, None, bucket, &config))
+        } else {
+            debug!(
+                "new limiter method {} route {}::{}",
+                &method, guard, route_name
+            );
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                burst = config.quota.burst_size().get(),
+                replenish_ms = config.quota.replenish_interval().as_millis() as u64,
+                max_keys = config.max_keys.map(NonZeroUsize::get),
+                algorithm = ?config.algorithm,
+                "new limiter"
+            );
+            Arc::new(Limiter::new(guard, Some(method), route_name, &config))
+        };
+        RegisteredRoute {
+            route_name: if limiter.is_bucket() {
+                route_name.into()
+            } else {
+                Arc::clone(&limiter.route_name)
+            },
+            limiter,
+            key_strategy: config.key_strategy.map(Arc::new),
+            #[cfg(feature = "metrics")]
+            counters: Arc::default(),
+        }
+    }
+
+    #[inline]
+    fn lookup<'a>(
+        routes: &'a RouteMap,
+        guard: &'static str,
+        method: Method,
+        route_name: &str,
+    ) -> Option<&'a RegisteredRoute> {
+        routes
+            .get(&(guard, method))
+            .and_then(|routes| routes.get(route_name))
+    }
+
+    /// Returns the registered [Limiter] of [RocketGovernable](crate::RocketGovernable)
+    /// implementation `T` governing `method` and `route_name`.
+    ///
+    /// This is the shared bucket limiter, if the route is in a bucket.
+    pub fn get<T>(method: Method, route_name: &str) -> Option<Arc<Limiter>> {
+        Self::lookup(&REG.routes.load(), type_name::<T>(), method, route_name)
+            .map(|route| Arc::clone(&route.limiter))
+    }
+
+    /// Returns the registered shared `bucket` [Limiter] of
+    /// [RocketGovernable](crate::RocketGovernable) implementation `T`.
+    pub fn get_bucket<T>(bucket: &str) -> Option<Arc<Limiter>> {
+        let guard = type_name::<T>();
+        Self::buckets().get(&(guard, bucket)).map(Arc::clone)
+    }
+
+    /// Snapshot of all registered limiters.
+    pub fn limiters() -> Vec<Arc<Limiter>> {
+        let buckets = Self::buckets();
+        REG.routes
+            .load()
+            .values()
+            .flat_map(|routes| routes.values())
+            .filter(|route| !route.limiter.is_bucket())
+            .map(|route| Arc::clone(&route.limiter))
+            .chain(buckets.values().map(Arc::clone))
+            .collect()
+    }
+
+    /// Snapshot of all registered routes with the type name of the
+    /// [RocketGovernable](crate::RocketGovernable) implementation and the
+    /// [Method].
+    #[cfg(feature = "metrics")]
+    pub(crate) fn routes() -> Vec<(&'static str, Method, RegisteredRoute)> {
+        REG.routes
+            .load()
+            .iter()
+            .flat_map(|((guard, method), routes)| {
+                routes
+                    .values()
+                    .map(move |route| (*guard, *method, route.clone()))
+            })
+            .collect()
+    }
+
+    /// Removes the idle keys of all registered limiters.
+    ///
+    /// Returns the number of removed keys.
+    ///
+    /// See [Limiter::retain_recent()] and [KeySweeper](crate::KeySweeper).
+    pub fn retain_recent() -> usize {
+        Self::limiters()
+            .iter()
+            .map(|limiter| limiter.retain_recent())
+            .sum()
     }
 }
 
 lazy_static! {
-    static ref REG: Registry = Registry::default();
+    static ref REG: Registry = Registry {
+        routes: ArcSwap::from_pointee(RouteMap::new()),
+        buckets: Mutex::new(BucketMap::new()),
+    };
 }
@@ -0,0 +1,171 @@
+//! Usage of the [Budget] per key of a limiter.
+
+use super::shards::Shards;
+use crate::{limit_key::LimitKey, Budget, BudgetState};
+use rocket::time::OffsetDateTime;
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
+
+/// Used requests of a key in the current period.
+#[derive(Clone, Copy, Debug)]
+struct Usage {
+    /// Start of the next period as UNIX timestamp.
+    reset_at: i64,
+    used: u32,
+}
+
+/// Store of the [Budget] usage per key split into [Shards].
+///
+/// If the store is bounded to `max_keys`, a new key evicts the keys with the
+/// oldest period and the least used requests.
+#[derive(Debug)]
+pub(crate) struct BudgetStore {
+    budget: Budget,
+    usage: Shards<LimitKey, Usage>,
+}
+
+impl BudgetStore {
+    /// Creates the store bounded to `max_keys` keys, if not `None`.
+    pub(crate) fn new(budget: Budget, max_keys: Option<NonZeroUsize>) -> Self {
+        Self {
+            budget,
+            usage: Shards::new(max_keys),
+        }
+    }
+
+    /// The [Budget].
+    pub(crate) fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// Usage of `key` at `now` in the current period.
+    fn usage(
+        &self,
+        usage: &HashMap<LimitKey, Usage>,
+        key: &LimitKey,
+        now: OffsetDateTime,
+    ) -> Usage {
+        match usage.get(key) {
+            Some(usage) if usage.reset_at > now.unix_timestamp() => *usage,
+            _ => Usage {
+                reset_at: self.budget.next_reset(now).unix_timestamp(),
+                used: 0,
+            },
+        }
+    }
+
+    fn state(&self, usage: &Usage, now: OffsetDateTime) -> BudgetState {
+        let reset = i128::from(usage.reset_at) * 1_000_000_000 - now.unix_timestamp_nanos();
+        BudgetState {
+            budget: self.budget,
+            remaining: self.budget.limit().get().saturating_sub(usage.used),
+            reset: Duration::from_nanos(u64::try_from(reset).unwrap_or_default()),
+        }
+    }
+
+    /// State of the budget of `key` without consuming a request.
+    pub(crate) fn peek(&self, key: &LimitKey) -> BudgetState {
+        let now = OffsetDateTime::now_utc();
+        let usage = self.usage.lock(key);
+        self.state(&self.usage(&usage, key, now), now)
+    }
+
+    /// Consumes one request of the budget of `key`.
+    ///
+    /// Returns the state after the request or the state of the exhausted
+    /// budget as error.
+    pub(crate) fn consume(&self, key: &LimitKey) -> Result<BudgetState, BudgetState> {
+        let now = OffsetDateTime::now_utc();
+        let mut usage = self.usage.lock(key);
+        let mut current = self.usage(&usage, key, now);
+        if current.used >= self.budget.limit().get() {
+            return Err(self.state(&current, now));
+        }
+
+        current.used += 1;
+        if let Some(tracked) = usage.get_mut(key) {
+            *tracked = current;
+        } else {
+            self.usage
+                .insert(&mut usage, key.clone(), current, |usage| {
+                    (usage.reset_at, usage.used)
+                });
+        }
+        Ok(self.state(&current, now))
+    }
+
+    /// Removes `key` and returns `true` if it has been tracked.
+    pub(crate) fn remove(&self, key: &LimitKey) -> bool {
+        self.usage.remove(key)
+    }
+
+    /// Removes all keys.
+    pub(crate) fn clear(&self) {
+        self.usage.clear();
+    }
+
+    /// Number of keys evicted because of `max_keys`.
+    #[inline]
+    pub(crate) fn evicted(&self) -> u64 {
+        self.usage.evicted()
+    }
+
+    /// Number of inserted keys.
+    #[inline]
+    pub(crate) fn inserted(&self) -> u64 {
+        self.usage.inserted()
+    }
+
+    /// Removes the keys of passed periods and shrinks the capacity.
+    ///
+    /// Returns the number of removed keys.
+    pub(crate) fn retain_recent(&self) -> usize {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let removed = self.usage.retain(|usage| usage.reset_at > now);
+        self.usage.shrink_to_fit();
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn test_consume() {
+        let store = BudgetStore::new(Budget::per_day(NonZeroU32::new(2).unwrap()), None);
+        let key = LimitKey::new(None, ["account"]);
+
+        assert_eq!(2, store.peek(&key).remaining());
+        assert_eq!(1, store.consume(&key).unwrap().remaining());
+        assert_eq!(0, store.consume(&key).unwrap().remaining());
+        let exhausted = store.consume(&key).unwrap_err();
+        assert_eq!(0, exhausted.remaining());
+        assert!(exhausted.reset() <= Duration::from_secs(86_400));
+
+        assert_eq!(0, store.retain_recent());
+        assert_eq!(0, store.peek(&key).remaining());
+        assert!(store.remove(&key));
+        assert_eq!(2, store.peek(&key).remaining());
+    }
+
+    #[test]
+    fn test_max_keys() {
+        let store = BudgetStore::new(
+            Budget::per_day(NonZeroU32::new(2).unwrap()),
+            NonZeroUsize::new(20),
+        );
+        let key = LimitKey::new(None, ["account"]);
+        assert!(store.consume(&key).is_ok());
+        assert!(store.consume(&key).is_ok());
+
+        for n in 0..100u32 {
+            assert!(store.consume(&LimitKey::new(None, [n.to_string()])).is_ok());
+        }
+        assert!(store.usage.len() <= 20);
+        assert_eq!(101, store.usage.len() as u64 + store.evicted());
+
+        // the keys with the least used requests are evicted first
+        assert!(store.consume(&key).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! Multiplier of the quota of an [Adaptive] limiter.
+
+use crate::Adaptive;
+use governor::Quota;
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+/// Multiplier of the quota of an [Adaptive] limiter controlled by additive
+/// increase and multiplicative decrease.
+///
+/// The multiplier takes the values of [Adaptive::multipliers()], which are
+/// indexed by the level.
+#[derive(Debug)]
+pub(crate) struct Multiplier {
+    adaptive: Adaptive,
+    multipliers: Vec<f64>,
+    level: AtomicUsize,
+    /// Time of the last adaption.
+    adapted: Mutex<Instant>,
+}
+
+impl Multiplier {
+    /// Creates the multiplier of `adaptive` at the level of `1.0` or of the
+    /// bound closest to `1.0`.
+    pub(crate) fn new(adaptive: Adaptive) -> Self {
+        let multipliers = adaptive.multipliers();
+        let level = multipliers
+            .iter()
+            .position(|multiplier| *multiplier >= 1.0)
+            .unwrap_or(multipliers.len() - 1);
+        Self {
+            adaptive,
+            multipliers,
+            level: AtomicUsize::new(level),
+            adapted: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// The [Adaptive] mode.
+    pub(crate) fn adaptive(&self) -> &Adaptive {
+        &self.adaptive
+    }
+
+    /// Number of levels.
+    pub(crate) fn levels(&self) -> usize {
+        self.multipliers.len()
+    }
+
+    /// The multiplier of `level`.
+    pub(crate) fn at(&self, level: usize) -> f64 {
+        self.multipliers[level]
+    }
+
+    /// The current level.
+    #[inline]
+    pub(crate) fn level(&self) -> usize {
+        self.level.load(Ordering::Relaxed)
+    }
+
+    /// Adapts the multiplier to the [LoadSignal](crate::LoadSignal), if the
+    /// interval elapsed since the last adaption.
+    ///
+    /// Returns the previous and the new multiplier, if changed.
+    pub(crate) fn adapt(&self) -> Option<(f64, f64)> {
+        // another request adapts concurrently
+        let mut adapted = self.adapted.try_lock().ok()?;
+        if adapted.elapsed() < self.adaptive.interval() {
+            return None;
+        }
+        *adapted = Instant::now();
+
+        let level = self.level();
+        let new_level = if self.adaptive.signal().is_overloaded() {
+            let target = self.multipliers[level] * self.adaptive.decrease();
+            self.multipliers
+                .iter()
+                .rposition(|multiplier| *multiplier <= target)
+                .unwrap_or(0)
+        } else {
+            (level + 1).min(self.levels() - 1)
+        };
+        if new_level == level {
+            return None;
+        }
+        self.level.store(new_level, Ordering::Relaxed);
+        Some((self.multipliers[level], self.multipliers[new_level]))
+    }
+}
+
+/// The `quota` with rate and burst size scaled by `multiplier`.
+pub(crate) fn scale(quota: Quota, multiplier: f64) -> Quota {
+    if (multiplier - 1.0).abs() < f64::EPSILON {
+        return quota;
+    }
+    let burst = (f64::from(quota.burst_size().get()) * multiplier).round() as u32;
+    let period = quota.replenish_interval().div_f64(multiplier);
+    match (Quota::with_period(period), NonZeroU32::new(burst.max(1))) {
+        (Some(scaled), Some(burst)) => scaled.allow_burst(burst),
+        _ => quota,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scale, Multiplier};
+    use crate::{Adaptive, LoadSignal};
+    use governor::Quota;
+    use std::{
+        num::NonZeroU32,
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    static OVERLOADED: AtomicBool = AtomicBool::new(false);
+
+    fn load() -> f64 {
+        if OVERLOADED.load(Ordering::SeqCst) {
+            2.0
+        } else {
+            0.5
+        }
+    }
+
+    #[test]
+    fn test_adapt() {
+        let multiplier = Multiplier::new(
+            Adaptive::new(LoadSignal::Load(load))
+                .with_bounds(0.25, 1.5)
+                .with_interval(Duration::ZERO),
+        );
+        assert_eq!(6, multiplier.levels());
+        assert_eq!(1.0, multiplier.at(multiplier.level()));
+
+        assert_eq!(Some((1.0, 1.25)), multiplier.adapt());
+        assert_eq!(Some((1.25, 1.5)), multiplier.adapt());
+        assert_eq!(None, multiplier.adapt());
+
+        OVERLOADED.store(true, Ordering::SeqCst);
+        assert_eq!(Some((1.5, 0.75)), multiplier.adapt());
+        assert_eq!(Some((0.75, 0.25)), multiplier.adapt());
+        assert_eq!(None, multiplier.adapt());
+    }
+
+    #[test]
+    fn test_levels() {
+        let adaptive = Adaptive::new(LoadSignal::Load(load))
+            .with_bounds(0.1, 2.05)
+            .with_increase(0.3);
+        let multiplier = Multiplier::new(adaptive);
+        assert_eq!(8, multiplier.levels());
+        assert_eq!(1.0, multiplier.at(multiplier.level()));
+        assert_eq!(0.1, multiplier.at(0));
+        assert_eq!(2.05, multiplier.at(multiplier.levels() - 1));
+
+        // 1.0 is added between the steps
+        let multiplier = Multiplier::new(adaptive.with_bounds(0.5, 1.5).with_increase(0.4));
+        assert_eq!(5, multiplier.levels());
+        assert_eq!(1.0, multiplier.at(multiplier.level()));
+        assert_eq!(0.9, multiplier.at(multiplier.level() - 1));
+
+        // starts at the bound closest to 1.0
+        let multiplier = Multiplier::new(adaptive.with_bounds(1.5, 3.0));
+        assert_eq!(1.5, multiplier.at(multiplier.level()));
+        let multiplier = Multiplier::new(adaptive.with_bounds(0.25, 0.5));
+        assert_eq!(0.5, multiplier.at(multiplier.level()));
+    }
+
+    #[test]
+    #[should_panic(expected = "need more than 64 steps")]
+    fn test_too_many_levels() {
+        let _ = Adaptive::new(LoadSignal::Load(load))
+            .with_bounds(0.1, 2.0)
+            .with_increase(0.001);
+    }
+
+    #[test]
+    #[should_panic(expected = "need more than 64 steps")]
+    fn test_too_wide_bounds() {
+        let _ = Adaptive::new(LoadSignal::Load(load)).with_bounds(0.25, 100.0);
+    }
+
+    #[test]
+    fn test_increase_before_bounds() {
+        let multiplier = Multiplier::new(
+            Adaptive::new(LoadSignal::Load(load))
+                .with_increase(5.0)
+                .with_bounds(0.25, 100.0),
+        );
+        assert_eq!(22, multiplier.levels());
+    }
+
+    #[test]
+    fn test_scale() {
+        let quota = Quota::per_minute(NonZeroU32::new(10).unwrap());
+
+        let scaled = scale(quota, 0.5);
+        assert_eq!(5, scaled.burst_size().get());
+        assert_eq!(Duration::from_secs(12), scaled.replenish_interval());
+
+        let scaled = scale(quota, 0.01);
+        assert_eq!(1, scaled.burst_size().get());
+        assert_eq!(quota, scale(quota, 1.0));
+    }
+}
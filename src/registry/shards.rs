@@ -0,0 +1,261 @@
+//! Map of the state per key split into shards hashed by key.
+//!
+//! Each shard has its own lock, so that checks of different keys don't wait
+//! for each other, and a bounded map evicts keys per shard.
+
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash, Hasher},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+/// Maximum number of shards.
+const MAX_SHARDS: usize = 64;
+
+/// Minimum number of keys per shard of a bounded map, so that the eviction
+/// of the oldest keys of a shard is close to the eviction of the oldest keys
+/// of the whole map.
+const MIN_SHARD_KEYS: usize = 32;
+
+/// Map of the state per key split into shards hashed by key.
+///
+/// If the map is bounded to `max_keys`, every shard is bounded to its share
+/// of `max_keys`. A new key evicts the entries with the oldest age of its
+/// shard in batches of about a tenth of the shard's share to amortize the
+/// cost of finding the oldest entries.
+#[derive(Debug)]
+pub(crate) struct Shards<K, V> {
+    shards: Box<[Mutex<HashMap<K, V>>]>,
+    hasher: RandomState,
+    max_keys: Option<NonZeroUsize>,
+    /// Maximum number of keys per shard.
+    shard_keys: Option<NonZeroUsize>,
+    evicted: AtomicU64,
+    inserted: AtomicU64,
+}
+
+impl<K, V> Shards<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Creates the map bounded to `max_keys` keys, if not `None`.
+    pub(crate) fn new(max_keys: Option<NonZeroUsize>) -> Self {
+        let len = max_keys.map_or(MAX_SHARDS, |max_keys| {
+            let len = (max_keys.get() / MIN_SHARD_KEYS).clamp(1, MAX_SHARDS);
+            // power of two not above len
+            1 << (usize::BITS - 1 - len.leading_zeros())
+        });
+        Self {
+            shards: (0..len).map(|_| Mutex::new(HashMap::new())).collect(),
+            hasher: RandomState::new(),
+            max_keys,
+            shard_keys: max_keys.and_then(|max_keys| NonZeroUsize::new(max_keys.get() / len)),
+            evicted: AtomicU64::new(0),
+            inserted: AtomicU64::new(0),
+        }
+    }
+
+    /// Maximum number of keys.
+    pub(crate) fn max_keys(&self) -> Option<NonZeroUsize> {
+        self.max_keys
+    }
+
+    /// Number of keys evicted because of `max_keys`.
+    #[inline]
+    pub(crate) fn evicted(&self) -> u64 {
+        self.evicted.load(Ordering::Relaxed)
+    }
+
+    /// Hash of `key`.
+    #[inline]
+    fn hash(&self, key: &K) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Number of inserted keys.
+    #[inline]
+    pub(crate) fn inserted(&self) -> u64 {
+        self.inserted.load(Ordering::Relaxed)
+    }
+
+    /// Locks the shard of `key`.
+    #[inline]
+    pub(crate) fn lock(&self, key: &K) -> MutexGuard<'_, HashMap<K, V>> {
+        let index = self.hash(key) as usize % self.shards.len();
+        self.shards[index].lock().unwrap()
+    }
+
+    /// Inserts the new `key` into its locked `shard` after evicting the
+    /// entries with the oldest `age`, if the shard is full.
+    pub(crate) fn insert<A>(
+        &self,
+        shard: &mut HashMap<K, V>,
+        key: K,
+        value: V,
+        age: impl Fn(&V) -> A,
+    ) where
+        K: Clone,
+        A: Ord + Copy,
+    {
+        if let Some(shard_keys) = self.shard_keys {
+            if shard.len() >= shard_keys.get() {
+                let evicted = evict_oldest(shard, shard_keys, age, |key| self.hash(key));
+                let _ = self.evicted.fetch_add(evicted as u64, Ordering::Relaxed);
+            }
+        }
+        let _ = shard.insert(key, value);
+        let _ = self.inserted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Removes `key` and returns `true` if it has been tracked.
+    pub(crate) fn remove(&self, key: &K) -> bool {
+        self.lock(key).remove(key).is_some()
+    }
+
+    /// Removes all keys.
+    pub(crate) fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap().clear();
+        }
+    }
+
+    /// Retains the entries for which `f` returns `true` shard by shard.
+    ///
+    /// Returns the number of removed entries.
+    pub(crate) fn retain(&self, mut f: impl FnMut(&V) -> bool) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut shard = shard.lock().unwrap();
+                let len = shard.len();
+                shard.retain(|_, value| f(value));
+                len - shard.len()
+            })
+            .sum()
+    }
+
+    /// Shrinks the capacity of the shards.
+    pub(crate) fn shrink_to_fit(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap().shrink_to_fit();
+        }
+    }
+
+    /// Number of tracked keys.
+    pub(crate) fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    /// Returns `true` if no key is tracked.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| shard.lock().unwrap().is_empty())
+    }
+}
+
+/// Evicts the entries of `map` with the oldest `age` to have space for a
+/// batch of about a tenth of `max_keys` new entries.
+///
+/// Entries of the same age are evicted in the order of the `hash` of their
+/// keys, so that exactly the needed number of entries is evicted.
+///
+/// Returns the number of evicted entries.
+fn evict_oldest<K, V, A>(
+    map: &mut HashMap<K, V>,
+    max_keys: NonZeroUsize,
+    age: impl Fn(&V) -> A,
+    hash: impl Fn(&K) -> u64,
+) -> usize
+where
+    K: Hash + Eq + Clone,
+    A: Ord + Copy,
+{
+    let max_keys = max_keys.get();
+    let keep = max_keys - 1 - max_keys / 10;
+    let remove = map.len().saturating_sub(keep);
+    if remove == 0 {
+        return 0;
+    }
+
+    let mut entries = map
+        .iter()
+        .map(|(key, value)| (age(value), hash(key), key))
+        .collect::<Vec<_>>();
+    let _ = entries.select_nth_unstable_by_key(remove - 1, |(age, hash, _)| (*age, *hash));
+    let evicted = entries[..remove]
+        .iter()
+        .map(|(_, _, key)| K::clone(key))
+        .collect::<Vec<_>>();
+    for key in &evicted {
+        let _ = map.remove(key);
+    }
+    evicted.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shards_len() {
+        assert_eq!(MAX_SHARDS, Shards::<u32, u32>::new(None).shards.len());
+
+        let shards = Shards::<u32, u32>::new(NonZeroUsize::new(20));
+        assert_eq!(1, shards.shards.len());
+        assert_eq!(NonZeroUsize::new(20), shards.shard_keys);
+
+        let shards = Shards::<u32, u32>::new(NonZeroUsize::new(10_000));
+        assert_eq!(MAX_SHARDS, shards.shards.len());
+        assert_eq!(NonZeroUsize::new(156), shards.shard_keys);
+
+        let shards = Shards::<u32, u32>::new(NonZeroUsize::new(100));
+        assert_eq!(2, shards.shards.len());
+        assert_eq!(NonZeroUsize::new(50), shards.shard_keys);
+    }
+
+    #[test]
+    fn test_evict_tied_ages() {
+        let shards = Shards::new(NonZeroUsize::new(20));
+        for key in 0..20u32 {
+            let mut shard = shards.lock(&key);
+            shards.insert(&mut shard, key, 0u32, |age| *age);
+        }
+        assert_eq!(20, shards.len());
+
+        // of the entries of the same age only a tenth is evicted
+        let mut shard = shards.lock(&20);
+        shards.insert(&mut shard, 20, 0, |age| *age);
+        drop(shard);
+        assert_eq!(3, shards.evicted());
+        assert_eq!(18, shards.len());
+    }
+
+    #[test]
+    fn test_evict_per_shard() {
+        let shards = Shards::new(NonZeroUsize::new(1_000));
+        for key in 0..10_000u32 {
+            let mut shard = shards.lock(&key);
+            shards.insert(&mut shard, key, key, |age| *age);
+        }
+        assert!(shards.len() <= 1_000);
+        assert_eq!(10_000, shards.len() as u64 + shards.evicted());
+        assert_eq!(10_000, shards.inserted());
+
+        // newest key of every shard is kept
+        assert!(shards.lock(&9_999).contains_key(&9_999));
+        assert!(!shards.lock(&0).contains_key(&0));
+
+        assert_eq!(shards.len(), shards.retain(|_| false));
+        assert!(shards.is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! Keyed state store of the registered rate limiters.
+//!
+//! In difference to the state stores of [governor] the [KeyStore] can be
+//! accessed besides the [RateLimiter](governor::RateLimiter) to reset keys,
+//! it supports peeking into the state of a key without consuming and it can
+//! be bounded to a maximum number of keys.
+
+use super::shards::Shards;
+use governor::{
+    nanos::Nanos,
+    state::{keyed::ShrinkableKeyedStateStore, StateStore},
+};
+use std::{hash::Hash, num::NonZeroUsize, sync::Arc};
+
+/// Keyed state store with the theoretical arrival time (TAT) per key.
+///
+/// The keys are split into [Shards], so that checks of different keys don't
+/// wait for each other.
+/// If the store is bounded to `max_keys`, a new key evicts the keys of its
+/// shard with the oldest TAT, which are the keys closest to the full quota.
+///
+/// Clones share the state. A [peeking()](Self::peeking()) clone doesn't
+/// replace the state with the decisions of its rate limiter.
+#[derive(Debug)]
+pub(crate) struct KeyStore<K> {
+    shards: Arc<Shards<K, Nanos>>,
+    peek: bool,
+}
+
+impl<K> KeyStore<K>
+where
+    K: Hash + Eq,
+{
+    /// Creates the store bounded to `max_keys` keys, if not `None`.
+    pub(crate) fn new(max_keys: Option<NonZeroUsize>) -> Self {
+        Self {
+            shards: Arc::new(Shards::new(max_keys)),
+            peek: false,
+        }
+    }
+
+    /// Clone of the store, which measures without replacing the state, to
+    /// peek into the state of keys by a rate limiter.
+    pub(crate) fn peeking(&self) -> Self {
+        Self {
+            shards: Arc::clone(&self.shards),
+            peek: true,
+        }
+    }
+
+    /// Maximum number of keys.
+    pub(crate) fn max_keys(&self) -> Option<NonZeroUsize> {
+        self.shards.max_keys()
+    }
+
+    /// Number of keys evicted because of `max_keys`.
+    #[inline]
+    pub(crate) fn evicted(&self) -> u64 {
+        self.shards.evicted()
+    }
+
+    /// Number of inserted keys.
+    #[inline]
+    pub(crate) fn inserted(&self) -> u64 {
+        self.shards.inserted()
+    }
+
+    /// Removes `key` and returns `true` if it has been tracked.
+    pub(crate) fn remove(&self, key: &K) -> bool {
+        self.shards.remove(key)
+    }
+
+    /// Removes all keys.
+    pub(crate) fn clear(&self) {
+        self.shards.clear();
+    }
+}
+
+impl<K> Clone for KeyStore<K> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: Arc::clone(&self.shards),
+            peek: self.peek,
+        }
+    }
+}
+
+impl<K> Default for KeyStore<K>
+where
+    K: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<K> StateStore for KeyStore<K>
+where
+    K: Hash + Eq + Clone,
+{
+    type Key = K;
+
+    fn measure_and_replace<T, F, E>(&self, key: &Self::Key, f: F) -> Result<T, E>
+    where
+        F: Fn(Option<Nanos>) -> Result<(T, Nanos), E>,
+    {
+        let mut shard = self.shards.lock(key);
+
+        if self.peek {
+            return f(shard.get(key).copied()).map(|(res, _)| res);
+        }
+
+        if let Some(tat) = shard.get_mut(key) {
+            let (res, next) = f(Some(*tat))?;
+            *tat = next;
+            Ok(res)
+        } else {
+            let (res, next) = f(None)?;
+            self.shards
+                .insert(&mut shard, key.clone(), next, |tat| *tat);
+            Ok(res)
+        }
+    }
+}
+
+impl<K> ShrinkableKeyedStateStore<K> for KeyStore<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn retain_recent(&self, drop_below: Nanos) {
+        let _ = self.shards.retain(|tat| *tat > drop_below);
+    }
+
+    fn shrink_to_fit(&self) {
+        self.shards.shrink_to_fit();
+    }
+
+    fn len(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.shards.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use governor::{clock::DefaultClock, Quota, RateLimiter};
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn test_peek_and_remove() {
+        let store = KeyStore::default();
+        let clock = DefaultClock::default();
+        let quota = Quota::per_hour(NonZeroU32::new(1).unwrap());
+        let limiter: RateLimiter<_, _, DefaultClock> =
+            RateLimiter::new(quota, store.clone(), &clock);
+        let peeker: RateLimiter<_, _, DefaultClock> =
+            RateLimiter::new(quota, store.peeking(), &clock);
+
+        assert!(peeker.check_key(&1u8).is_ok());
+        assert_eq!(0, store.len());
+        assert!(limiter.check_key(&1u8).is_ok());
+        assert_eq!(1, store.len());
+        assert!(peeker.check_key(&1u8).is_err());
+        assert!(limiter.check_key(&1u8).is_err());
+
+        assert!(store.remove(&1u8));
+        assert!(!store.remove(&1u8));
+        assert!(limiter.check_key(&1u8).is_ok());
+
+        store.clear();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_evict_oldest() {
+        let store = KeyStore::new(NonZeroUsize::new(20));
+        let limiter: RateLimiter<_, _, DefaultClock> = RateLimiter::new(
+            Quota::per_hour(NonZeroU32::new(1).unwrap()),
+            store.clone(),
+            &DefaultClock::default(),
+        );
+
+        for key in 0..20u8 {
+            assert!(limiter.check_key(&key).is_ok());
+        }
+        assert_eq!(20, store.len());
+        assert_eq!(0, store.evicted());
+
+        // batch of the 3 oldest keys is evicted for the new key
+        assert!(limiter.check_key(&20u8).is_ok());
+        assert_eq!(18, store.len());
+        assert_eq!(3, store.evicted());
+        for key in 0..2u8 {
+            assert!(limiter.check_key(&key).is_ok());
+        }
+        for key in 3..21u8 {
+            assert!(limiter.check_key(&key).is_err());
+        }
+        assert_eq!(20, store.len());
+        assert_eq!(3, store.evicted());
+    }
+}
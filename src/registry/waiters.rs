@@ -0,0 +1,94 @@
+//! Waiting requests per key of a [Limiter](super::Limiter) in queue mode.
+
+use crate::limit_key::LimitKey;
+use std::{collections::HashMap, num::NonZeroUsize, sync::Mutex};
+
+/// Number of waiting requests per key.
+#[derive(Debug, Default)]
+pub(crate) struct Waiters(Mutex<HashMap<LimitKey, usize>>);
+
+impl Waiters {
+    /// Enters a waiting request of `key`, if there are less than
+    /// `max_waiting` requests of `key` waiting.
+    ///
+    /// The request leaves on drop of the returned [Waiter].
+    pub(crate) fn enter(&self, key: &LimitKey, max_waiting: NonZeroUsize) -> Option<Waiter<'_>> {
+        let mut waiters = self.0.lock().unwrap();
+        let ahead = if let Some(n) = waiters.get_mut(key) {
+            if *n >= max_waiting.get() {
+                return None;
+            }
+            *n += 1;
+            *n - 1
+        } else {
+            let _ = waiters.insert(key.clone(), 1);
+            0
+        };
+
+        Some(Waiter {
+            waiters: self,
+            key: key.clone(),
+            ahead,
+        })
+    }
+
+    /// Number of all waiting requests.
+    pub(crate) fn len(&self) -> usize {
+        self.0.lock().unwrap().values().sum()
+    }
+}
+
+/// A waiting request, which leaves the [Waiters] on drop.
+#[derive(Debug)]
+pub(crate) struct Waiter<'a> {
+    waiters: &'a Waiters,
+    key: LimitKey,
+    ahead: usize,
+}
+
+impl Waiter<'_> {
+    /// Number of requests of the key, which have been waiting already on
+    /// entering.
+    pub(crate) fn ahead(&self) -> usize {
+        self.ahead
+    }
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        let mut waiters = self.waiters.0.lock().unwrap();
+        if let Some(n) = waiters.get_mut(&self.key) {
+            *n -= 1;
+            if *n == 0 {
+                let _ = waiters.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_waiting() {
+        let waiters = Waiters::default();
+        let key = LimitKey::from("192.0.2.1".parse::<std::net::IpAddr>().unwrap());
+        let max_waiting = NonZeroUsize::new(2).unwrap();
+
+        let first = waiters.enter(&key, max_waiting);
+        let second = waiters.enter(&key, max_waiting);
+        assert_eq!(Some(0), first.as_ref().map(Waiter::ahead));
+        assert_eq!(Some(1), second.as_ref().map(Waiter::ahead));
+        assert!(waiters.enter(&key, max_waiting).is_none());
+        assert_eq!(2, waiters.len());
+
+        drop(first);
+        assert_eq!(1, waiters.len());
+        assert!(waiters.enter(&key, max_waiting).is_some());
+
+        drop(second);
+        assert_eq!(0, waiters.len());
+        assert!(waiters.0.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,378 @@
+//! Fixed window and sliding window counter limiters.
+
+use super::{shards::Shards, Allowed, Denied};
+use crate::limit_key::LimitKey;
+use governor::Quota;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Requests of a key counted in the current and the previous window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Counter {
+    /// Index of the current window since the UNIX epoch.
+    window: u64,
+    count: u32,
+    /// Count of the previous window.
+    previous: u32,
+    /// Nanoseconds since the UNIX epoch of the last counted request.
+    updated: u64,
+}
+
+impl Counter {
+    /// The counter moved forward to `window`.
+    fn at(self, window: u64) -> Self {
+        if window == self.window {
+            self
+        } else if window == self.window + 1 {
+            Self {
+                window,
+                count: 0,
+                previous: self.count,
+                ..self
+            }
+        } else {
+            Self {
+                window,
+                count: 0,
+                previous: 0,
+                ..self
+            }
+        }
+    }
+}
+
+/// Rate limiter counting the requests per key in windows aligned to the
+/// UNIX epoch.
+///
+/// The sliding window counter weighs the count of the previous window by
+/// its share in the sliding window ending now.
+///
+/// The [Quota] is provided per check to follow a
+/// [QuotaSchedule](crate::QuotaSchedule). The counts are kept, if the
+/// scheduled quotas have the same period.
+#[derive(Debug)]
+pub(crate) struct WindowLimiter {
+    sliding: bool,
+    counters: Shards<LimitKey, Counter>,
+}
+
+impl WindowLimiter {
+    /// Creates the limiter with windows of the period of the checked quota.
+    pub(crate) fn new(sliding: bool, max_keys: Option<NonZeroUsize>) -> Self {
+        Self {
+            sliding,
+            counters: Shards::new(max_keys),
+        }
+    }
+
+    /// Length of the window of `quota` in nanoseconds.
+    ///
+    /// The period is rounded to milliseconds, because the replenish interval
+    /// of the [Quota] is truncated, e.g. for 7 requests per minute.
+    fn length_of(quota: &Quota) -> u64 {
+        let period = (quota.replenish_interval() * quota.burst_size().get()).as_nanos();
+        let rounded = (period + 500_000) / 1_000_000 * 1_000_000;
+        let length = if rounded == 0 { period } else { rounded };
+        u64::try_from(length).unwrap_or(u64::MAX).max(1)
+    }
+
+    /// Nanoseconds since the UNIX epoch.
+    fn now() -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        u64::try_from(now.as_nanos()).unwrap_or(u64::MAX)
+    }
+
+    /// Counter of `key` at `now` in windows of `length` or a new counter.
+    fn counter(
+        counters: &HashMap<LimitKey, Counter>,
+        key: &LimitKey,
+        now: u64,
+        length: u64,
+    ) -> Counter {
+        let window = now / length;
+        counters.get(key).map_or(
+            Counter {
+                window,
+                count: 0,
+                previous: 0,
+                updated: now,
+            },
+            |counter| counter.at(window),
+        )
+    }
+
+    /// Number of requests `counter` can do at `now` by `quota` without the
+    /// capacity `reserved` for higher priority classes or the [Denied]
+    /// request.
+    fn state(
+        &self,
+        counter: &Counter,
+        now: u64,
+        quota: Quota,
+        reserved: u32,
+    ) -> Result<u32, Denied> {
+        let length = Self::length_of(&quota);
+        let elapsed = u128::from(now % length);
+        let length = u128::from(length);
+        let limit = u128::from(quota.burst_size().get().saturating_sub(reserved).max(1));
+        let count = u128::from(counter.count);
+        let previous = if self.sliding {
+            u128::from(counter.previous)
+        } else {
+            0
+        };
+
+        // scaled by the window length to stay in integers
+        let used = previous * (length - elapsed) + count * length;
+        let remaining = (limit * length).saturating_sub(used) / length;
+        if remaining > 0 {
+            return Ok(u32::try_from(remaining).unwrap_or(u32::MAX));
+        }
+
+        let until_end = length - elapsed;
+        let (wait, reset) = if !self.sliding {
+            (until_end, until_end)
+        } else if count < limit {
+            // the weight of the previous window decreases in this window
+            let allowed_previous = (limit - count - 1) * length / previous;
+            (until_end - allowed_previous, until_end + length)
+        } else {
+            // this window becomes the previous window
+            let allowed_current = (limit - 1) * length / count;
+            (until_end + length - allowed_current, until_end + length)
+        };
+
+        Err(Denied {
+            quota,
+            wait_time: Self::duration(wait),
+            reset: Some(Self::duration(reset)),
+            budget: None,
+        })
+    }
+
+    fn duration(nanos: u128) -> Duration {
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+
+    /// Check and consume one request of `key` by `quota` without the
+    /// capacity `reserved` for higher priority classes.
+    pub(crate) fn check_key(
+        &self,
+        key: &LimitKey,
+        quota: Quota,
+        reserved: u32,
+    ) -> Result<Allowed, Denied> {
+        let now = Self::now();
+        let mut counters = self.counters.lock(key);
+        let counter = Self::counter(&counters, key, now, Self::length_of(&quota));
+        let remaining = self.state(&counter, now, quota, reserved)?;
+
+        let counter = Counter {
+            count: counter.count.saturating_add(1),
+            updated: now,
+            ..counter
+        };
+        if let Some(current) = counters.get_mut(key) {
+            *current = counter;
+        } else {
+            self.counters
+                .insert(&mut counters, key.clone(), counter, |counter| {
+                    counter.updated
+                });
+        }
+
+        Ok(Allowed {
+            quota,
+            remaining: remaining - 1,
+            budget: None,
+        })
+    }
+
+    /// State of `key` by `quota` without the capacity `reserved` for higher
+    /// priority classes and without consuming a request.
+    pub(crate) fn peek_key(
+        &self,
+        key: &LimitKey,
+        quota: Quota,
+        reserved: u32,
+    ) -> Result<u32, Denied> {
+        let now = Self::now();
+        let counters = self.counters.lock(key);
+        let counter = Self::counter(&counters, key, now, Self::length_of(&quota));
+        self.state(&counter, now, quota, reserved)
+    }
+
+    /// Maximum number of keys.
+    pub(crate) fn max_keys(&self) -> Option<NonZeroUsize> {
+        self.counters.max_keys()
+    }
+
+    /// Number of keys evicted because of `max_keys`.
+    #[inline]
+    pub(crate) fn evicted(&self) -> u64 {
+        self.counters.evicted()
+    }
+
+    /// Number of inserted keys.
+    #[inline]
+    pub(crate) fn inserted(&self) -> u64 {
+        self.counters.inserted()
+    }
+
+    /// Removes `key` and returns `true` if it has been tracked.
+    pub(crate) fn remove(&self, key: &LimitKey) -> bool {
+        self.counters.remove(key)
+    }
+
+    /// Removes all keys.
+    pub(crate) fn clear(&self) {
+        self.counters.clear();
+    }
+
+    /// Removes the keys without requests in the counted windows of `quota`
+    /// and shrinks the capacity.
+    ///
+    /// Returns the number of removed keys.
+    pub(crate) fn retain_recent(&self, quota: Quota) -> usize {
+        let window = Self::now() / Self::length_of(&quota);
+        let removed = self.counters.retain(|counter| {
+            let counter = counter.at(window);
+            counter.count > 0 || (self.sliding && counter.previous > 0)
+        });
+        self.counters.shrink_to_fit();
+        removed
+    }
+
+    /// Number of tracked keys.
+    pub(crate) fn len(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Returns `true` if no key is tracked.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU32;
+
+    fn limiter(sliding: bool) -> WindowLimiter {
+        WindowLimiter::new(sliding, None)
+    }
+
+    fn quota() -> Quota {
+        Quota::per_minute(NonZeroU32::new(10).unwrap())
+    }
+
+    fn counter(count: u32, previous: u32) -> Counter {
+        Counter {
+            window: 1,
+            count,
+            previous,
+            updated: 0,
+        }
+    }
+
+    const MINUTE: u64 = 60_000_000_000;
+
+    #[test]
+    fn test_window_length() {
+        assert_eq!(MINUTE, WindowLimiter::length_of(&quota()));
+        let quota = Quota::per_minute(NonZeroU32::new(7).unwrap());
+        assert_eq!(MINUTE, WindowLimiter::length_of(&quota));
+    }
+
+    #[test]
+    fn test_fixed_window() {
+        let limiter = limiter(false);
+        let now = MINUTE + MINUTE / 4;
+
+        assert_eq!(
+            Ok(10),
+            limiter
+                .state(&counter(0, 10), now, quota(), 0)
+                .map_err(|_| ())
+        );
+        assert_eq!(
+            Ok(1),
+            limiter
+                .state(&counter(9, 10), now, quota(), 0)
+                .map_err(|_| ())
+        );
+
+        let denied = limiter.state(&counter(10, 0), now, quota(), 0).unwrap_err();
+        assert_eq!(Duration::from_secs(45), denied.wait_time);
+        assert_eq!(Some(Duration::from_secs(45)), denied.reset);
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let limiter = limiter(true);
+        let now = MINUTE + MINUTE / 4;
+
+        // 3/4 of the previous count is in the sliding window
+        assert_eq!(
+            Ok(10),
+            limiter
+                .state(&counter(0, 0), now, quota(), 0)
+                .map_err(|_| ())
+        );
+        assert_eq!(
+            Ok(4),
+            limiter
+                .state(&counter(0, 8), now, quota(), 0)
+                .map_err(|_| ())
+        );
+        assert_eq!(
+            Ok(1),
+            limiter
+                .state(&counter(3, 8), now, quota(), 0)
+                .map_err(|_| ())
+        );
+
+        // allowed when 5/8 of the previous count is in the sliding window
+        let denied = limiter.state(&counter(4, 8), now, quota(), 0).unwrap_err();
+        assert_eq!(Duration::from_millis(7500), denied.wait_time);
+        assert_eq!(Some(Duration::from_secs(105)), denied.reset);
+
+        // allowed when 9/10 of this count is in the next sliding window
+        let denied = limiter.state(&counter(10, 0), now, quota(), 0).unwrap_err();
+        assert_eq!(Duration::from_secs(51), denied.wait_time);
+    }
+
+    #[test]
+    fn test_reserved() {
+        let limiter = limiter(false);
+        let now = MINUTE + MINUTE / 4;
+
+        assert_eq!(
+            Ok(3),
+            limiter
+                .state(&counter(5, 0), now, quota(), 2)
+                .map_err(|_| ())
+        );
+        assert!(limiter.state(&counter(8, 0), now, quota(), 2).is_err());
+        assert_eq!(
+            Ok(2),
+            limiter
+                .state(&counter(8, 0), now, quota(), 0)
+                .map_err(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_counter_at() {
+        let counter = counter(3, 8);
+        assert_eq!(counter, counter.at(1));
+        assert_eq!((0, 3), (counter.at(2).count, counter.at(2).previous));
+        assert_eq!((0, 0), (counter.at(3).count, counter.at(3).previous));
+    }
+}
@@ -8,7 +8,7 @@
 //! [ReqState::default()] provides an object which might be only useful in the
 //! inner library usage.
 
-use super::{NonZeroU32, Quota};
+use super::{BudgetState, NonZeroU32, Quota};
 use rocket::Request;
 
 /// `ReqState` is the data struct to handle information about [`Quota`] and
@@ -35,6 +35,13 @@ pub struct ReqState {
     ///
     /// Limitation starts below `0`.
     pub(crate) request_capacity: u32,
+
+    /// Seconds the [Request] would have been limited, if the route is governed
+    /// in shadow mode.
+    pub(crate) shadow_wait: Option<u64>,
+
+    /// State of the usage [Budget](super::Budget) of the key.
+    pub(crate) budget: Option<BudgetState>,
 }
 
 impl ReqState {
@@ -44,9 +51,28 @@ impl ReqState {
             is_default: false,
             quota,
             request_capacity,
+            shadow_wait: None,
+            budget: None,
+        }
+    }
+
+    /// Create new [`ReqState`] for a would-be limited [Request] in shadow mode
+    pub(crate) fn new_shadow(quota: Quota, wait_time: u64) -> Self {
+        Self {
+            is_default: false,
+            quota,
+            request_capacity: 0,
+            shadow_wait: Some(wait_time),
+            budget: None,
         }
     }
 
+    /// Set the state of the usage [Budget](super::Budget)
+    pub(crate) fn with_budget(mut self, budget: Option<BudgetState>) -> Self {
+        self.budget = budget;
+        self
+    }
+
     /// Retrieve cached [`ReqState`] from [`Request::local_cache()`]
     ///
     /// Be careful, you'll set cache value to [`ReqState::default()`] if there
@@ -75,6 +101,26 @@ impl ReqState {
     pub fn request_capacity(&self) -> u32 {
         self.request_capacity
     }
+
+    /// Seconds the [Request] would have been limited, if the route is governed
+    /// in shadow mode.
+    ///
+    /// `None` if the route is not in shadow mode or the [Request] is not
+    /// over the limit.
+    ///
+    /// See [RocketGovernable::shadow()](super::RocketGovernable::shadow()).
+    pub fn shadow_wait(&self) -> Option<u64> {
+        self.shadow_wait
+    }
+
+    /// State of the usage [Budget](super::Budget) of the key.
+    ///
+    /// `None` if the route has no budget.
+    ///
+    /// See [RocketGovernable::budget()](super::RocketGovernable::budget()).
+    pub fn budget(&self) -> Option<&BudgetState> {
+        self.budget.as_ref()
+    }
 }
 
 impl Default for ReqState {
@@ -86,6 +132,8 @@ impl Default for ReqState {
             is_default: true,
             quota: Quota::per_second(NonZeroU32::new(1).unwrap()),
             request_capacity: 0,
+            shadow_wait: None,
+            budget: None,
         }
     }
 }
@@ -2,8 +2,11 @@
 
 #[cfg(feature = "limit_info")]
 use super::ReqState;
-use super::{Method, NonZeroU32, Quota};
-use rocket::async_trait;
+use super::{
+    observer::DecisionListener, Adaptive, Algorithm, Budget, KeyPart, Method, NonZeroU32,
+    NonZeroUsize, Priority, QueueMode, Quota, QuotaSchedule, RouteIdentity,
+};
+use rocket::{async_trait, Request};
 
 /// The [RocketGovernable] guard trait.
 ///
@@ -113,6 +116,518 @@ pub trait RocketGovernable<'r> {
         state.request_capacity <= 1
     }
 
+    /// Returns `true` if the limiter for `method` and `route_name` runs in
+    /// __shadow mode__ (dry-run).
+    ///
+    /// In shadow mode the limiter is evaluated and consumed like in normal
+    /// operation, but a request which would be rate limited is only logged
+    /// and never rejected. The guard always succeeds.
+    ///
+    /// This is useful to roll out a new or stricter [Quota] and watch who
+    /// would be limited, before it is enforced.
+    ///
+    /// With feature __limit_info__ and attached
+    /// [`LimitHeaderGen`](crate::LimitHeaderGen) fairing the would-be
+    /// rejection is reported in the
+    /// [`X_RATELIMIT_SHADOW`](crate::header::X_RATELIMIT_SHADOW) header,
+    /// if allowed by `limit_info_allow()`.
+    ///
+    /// The trait implementation returns `false` for every route.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(1u32))
+    ///     }
+    ///
+    ///     fn shadow(_method: Method, route_name: &str) -> bool {
+    ///         route_name == "new_endpoint"
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn shadow(method: Method, route_name: &str) -> bool {
+        let (_, _) = (method, route_name); // unused warning
+
+        false
+    }
+
+    /// Returns the [QueueMode] of the limiter for `method` and `route_name`.
+    ///
+    /// In queue mode a rate limited request waits in the guard until the
+    /// limiter allows it, which smooths bursts instead of rejecting them.
+    /// The request is rejected, if the wait would exceed the
+    /// [maximum delay](QueueMode::max_delay()) or if too many requests of
+    /// the key are waiting.
+    /// A delayed request is reported with
+    /// [Decision::Delayed](crate::observer::Decision::Delayed).
+    ///
+    /// Routes in [shadow mode](RocketGovernable::shadow()) never wait.
+    ///
+    /// The trait implementation returns `None`, so limited requests are
+    /// rejected immediately.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, Quota, QueueMode, RocketGovernable};
+    /// use std::time::Duration;
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(10u32))
+    ///     }
+    ///
+    ///     fn queue_mode(_method: Method, route_name: &str) -> Option<QueueMode> {
+    ///         if route_name.starts_with("internal_") {
+    ///             Some(QueueMode::new(Duration::from_secs(2)).with_jitter(Duration::from_millis(20)))
+    ///         } else {
+    ///             None
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn queue_mode(method: Method, route_name: &str) -> Option<QueueMode> {
+        let (_, _) = (method, route_name); // unused warning
+
+        None
+    }
+
+    /// Returns the maximum number of keys (client addresses) tracked by the
+    /// limiter for `method` and `route_name`.
+    ///
+    /// This bounds the memory of the limiter, also if clients spray requests
+    /// from random addresses.
+    /// If a new key would exceed the maximum, the keys with the oldest state
+    /// are evicted. These are the keys closest to the full [Quota], but an
+    /// evicted key which is rate limited gets the full [Quota] again.
+    /// The usage of a [`budget()`](RocketGovernable::budget()) is bounded
+    /// to the same maximum, evicting the keys of the oldest periods with the
+    /// least used requests first.
+    /// Evictions are logged and counted in
+    /// [Limiter::evicted()](crate::Limiter::evicted()).
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns `None`, so the number of keys is
+    /// unbounded.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, NonZeroUsize, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(1u32))
+    ///     }
+    ///
+    ///     fn max_keys(_method: Method, _route_name: &str) -> Option<NonZeroUsize> {
+    ///         NonZeroUsize::new(100_000)
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn max_keys(method: Method, route_name: &str) -> Option<NonZeroUsize> {
+        let (_, _) = (method, route_name); // unused warning
+
+        None
+    }
+
+    /// Returns the name of a shared bucket for `method` and `route_name`.
+    ///
+    /// All routes and methods with the same bucket name share one limiter
+    /// and consume from the same [Quota], e.g. to limit all write endpoints
+    /// together. Logs, headers and
+    /// [DecisionRecords](crate::observer::DecisionRecord) still report the
+    /// route of the request.
+    ///
+    /// The [Quota] and [`max_keys()`](RocketGovernable::max_keys()) of the
+    /// bucket are taken from the first route requested in the bucket, so
+    /// return the same values for all routes of a bucket.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns `None`, so every route has its own
+    /// limiter.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(method: Method, _route_name: &str) -> Quota {
+    ///         if method == Method::Get {
+    ///             Quota::per_second(Self::nonzero(10u32))
+    ///         } else {
+    ///             Quota::per_minute(Self::nonzero(100u32))
+    ///         }
+    ///     }
+    ///
+    ///     fn bucket(method: Method, _route_name: &str) -> Option<&'static str> {
+    ///         match method {
+    ///             Method::Post | Method::Put | Method::Patch | Method::Delete => Some("writes"),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn bucket(method: Method, route_name: &str) -> Option<&'static str> {
+        let (_, _) = (method, route_name); // unused warning
+
+        None
+    }
+
+    /// Returns the [Algorithm] of the limiter for `method` and `route_name`.
+    ///
+    /// The window algorithms count the requests per window of the [Quota]
+    /// period, e.g. `Quota::per_hour(nonzero(1000))` allows 1000 requests
+    /// per calendar hour. Limited requests are rejected with
+    /// [LimitError::GovernedWindow](crate::LimitError::GovernedWindow),
+    /// which reports the reset of the window.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns [Algorithm::Gcra].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Algorithm, Method, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_hour(Self::nonzero(1000u32))
+    ///     }
+    ///
+    ///     fn algorithm(_method: Method, _route_name: &str) -> Algorithm {
+    ///         Algorithm::FixedWindow
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn algorithm(method: Method, route_name: &str) -> Algorithm {
+        let (_, _) = (method, route_name); // unused warning
+
+        Algorithm::Gcra
+    }
+
+    /// Returns the usage [Budget] of the limiter for `method` and
+    /// `route_name`, which caps the requests of a key per calendar period in
+    /// addition to the [Quota].
+    ///
+    /// A request is counted against the budget only if it is allowed by the
+    /// [Quota]. Requests over budget are rejected with
+    /// [LimitError::BudgetExhausted](crate::LimitError::BudgetExhausted)
+    /// until the budget is reset at the start of the next period.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns `None`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Budget, Method, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(10u32))
+    ///     }
+    ///
+    ///     fn budget(_method: Method, _route_name: &str) -> Option<Budget> {
+    ///         Some(Budget::per_month(Self::nonzero(50_000u32)))
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn budget(method: Method, route_name: &str) -> Option<Budget> {
+        let (_, _) = (method, route_name); // unused warning
+
+        None
+    }
+
+    /// Returns the [QuotaSchedule] of the limiter for `method` and
+    /// `route_name`, which replaces the [Quota] at hours of weekdays.
+    ///
+    /// The limiter switches to the scheduled quota at the boundaries of the
+    /// hours and keeps the state of the keys, so requests done before the
+    /// switch still count.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns `None`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, Quota, QuotaSchedule, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(50u32))
+    ///     }
+    ///
+    ///     fn quota_schedule(_method: Method, _route_name: &str) -> Option<QuotaSchedule> {
+    ///         Some(QuotaSchedule::new().with_quota(
+    ///             &QuotaSchedule::WEEKDAYS,
+    ///             8,
+    ///             20,
+    ///             Quota::per_second(Self::nonzero(10u32)),
+    ///         ))
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn quota_schedule(method: Method, route_name: &str) -> Option<QuotaSchedule> {
+        let (_, _) = (method, route_name); // unused warning
+
+        None
+    }
+
+    /// Returns the [Adaptive] mode of the limiter for `method` and
+    /// `route_name`, which scales the [Quota] up or down by the load of the
+    /// server.
+    ///
+    /// The multiplier of the quota is adapted on requests to the limiter
+    /// and is provided by [Limiter::multiplier()](crate::Limiter::multiplier()).
+    /// The [LoadSignals](crate::LoadSignal) of requests in flight and of the
+    /// latency require the [LoadMonitor](crate::LoadMonitor) fairing.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns `None`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Adaptive, LoadSignal, Method, Quota, RocketGovernable};
+    /// use std::time::Duration;
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(10u32))
+    ///     }
+    ///
+    ///     fn adaptive(_method: Method, _route_name: &str) -> Option<Adaptive> {
+    ///         Some(Adaptive::new(LoadSignal::Latency(Duration::from_millis(200))).with_bounds(0.1, 3.0))
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn adaptive(method: Method, route_name: &str) -> Option<Adaptive> {
+        let (_, _) = (method, route_name); // unused warning
+
+        None
+    }
+
+    /// Returns the [Priority] class of the `request`, e.g. by its
+    /// authentication.
+    ///
+    /// A request can use the capacity of the [Quota] without the capacity
+    /// [reserved](RocketGovernable::reserved_capacity()) for the higher
+    /// classes.
+    ///
+    /// The trait implementation returns [Priority::Normal].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket_governor::{Method, Priority, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(100u32))
+    ///     }
+    ///
+    ///     fn priority(request: &Request<'_>) -> Priority {
+    ///         if request.headers().contains("Authorization") {
+    ///             Priority::High
+    ///         } else {
+    ///             Priority::Low
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn priority(request: &Request<'_>) -> Priority {
+        let _ = request; // unused warning
+
+        Priority::Normal
+    }
+
+    /// Returns the capacity of the [Quota] for `method` and `route_name`
+    /// reserved for requests of `priority` and higher classes.
+    ///
+    /// Requests of lower classes than `priority` are limited, when only the
+    /// reserved capacity of the burst is left, so anonymous traffic can't
+    /// starve e.g. paying customers in a shared
+    /// [bucket](RocketGovernable::bucket()). A class has at least a burst of
+    /// 1 request.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns `0`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, Priority, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_second(Self::nonzero(100u32))
+    ///     }
+    ///
+    ///     fn bucket(_method: Method, _route_name: &str) -> Option<&'static str> {
+    ///         Some("api")
+    ///     }
+    ///
+    ///     fn reserved_capacity(_method: Method, _route_name: &str, priority: Priority) -> u32 {
+    ///         match priority {
+    ///             // low priority requests can use up to 70 requests of the burst
+    ///             Priority::High => 20,
+    ///             Priority::Critical => 10,
+    ///             _ => 0,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn reserved_capacity(method: Method, route_name: &str, priority: Priority) -> u32 {
+        let (_, _, _) = (method, route_name, priority); // unused warning
+
+        0
+    }
+
+    /// Returns the [RouteIdentity] to identify the route of a request.
+    ///
+    /// Every identified route gets its own limiter per [Method] and the
+    /// identity is provided as `route_name` to the other methods of the
+    /// [RocketGovernable].
+    ///
+    /// The trait implementation returns [RouteIdentity::Name], so routes are
+    /// identified by their name and requests of unnamed routes fail.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{Method, Quota, RocketGovernable, RouteIdentity};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, route_name: &str) -> Quota {
+    ///         if route_name.starts_with("/api/v1/") {
+    ///             Quota::per_second(Self::nonzero(10u32))
+    ///         } else {
+    ///             Quota::per_second(Self::nonzero(1u32))
+    ///         }
+    ///     }
+    ///
+    ///     fn route_identity() -> RouteIdentity {
+    ///         RouteIdentity::Uri
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn route_identity() -> RouteIdentity {
+        RouteIdentity::Name
+    }
+
+    /// Returns the [KeyParts](KeyPart) of the [LimitKey](crate::LimitKey)
+    /// for `method` and `route_name`.
+    ///
+    /// Every distinct key of a route has its own rate limit state.
+    /// Combine the client IP address with route parameters for
+    /// per-resource limits or leave the client out to limit a resource for
+    /// all clients together. Form fields of the request body are not
+    /// available in a request guard.
+    ///
+    /// Like [`quota()`](RocketGovernable::quota()) this is called only once
+    /// per method/route_name combination.
+    ///
+    /// The trait implementation returns [KeyPart::ClientIp], so every client
+    /// IP address is limited on its own.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use rocket_governor::{KeyPart, Method, Quota, RocketGovernable};
+    ///
+    /// pub struct RateLimitGuard;
+    ///
+    /// impl<'r> RocketGovernable<'r> for RateLimitGuard {
+    ///     fn quota(_method: Method, _route_name: &str) -> Quota {
+    ///         Quota::per_hour(Self::nonzero(5u32))
+    ///     }
+    ///
+    ///     fn key_parts(_method: Method, route_name: &str) -> Vec<KeyPart> {
+    ///         if route_name == "account_reset" {
+    ///             // `/accounts/<id>/reset` per account for all clients
+    ///             vec![KeyPart::Param("id")]
+    ///         } else {
+    ///             vec![KeyPart::ClientIp]
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn key_parts(method: Method, route_name: &str) -> Vec<KeyPart> {
+        let (_, _) = (method, route_name); // unused warning
+
+        vec![KeyPart::ClientIp]
+    }
+
+    /// Returns the [DecisionListener] of the [RocketGovernable], which is
+    /// notified about every decision of the guard.
+    ///
+    /// Listeners for all guards can be registered with
+    /// [register_listener()](crate::observer::register_listener()).
+    ///
+    /// The trait implementation returns `None`.
+    ///
+    /// See [observer](crate::observer) module for an example.
+    #[inline]
+    fn listener() -> Option<&'static dyn DecisionListener> {
+        None
+    }
+
     /// Converts a non-zero number [u32] to [NonZeroU32](std::num::NonZeroU32).
     ///
     /// Number zero/0 becomes 1.
@@ -0,0 +1,71 @@
+//! Identification of the governed route.
+
+use rocket::Route;
+
+/// Identifies the route of a request, which gets its own rate limiter
+/// per [Method](crate::Method).
+///
+/// The identity is provided as `route_name` to the methods of
+/// [RocketGovernable](crate::RocketGovernable), the [Registry](crate::Registry)
+/// and [DecisionRecord](crate::observer::DecisionRecord).
+///
+/// See [RocketGovernable::route_identity()](crate::RocketGovernable::route_identity()).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RouteIdentity {
+    /// Name of the route, which is the function name for routes declared
+    /// with the route attributes of Rocket.
+    ///
+    /// Routes with the same name share the limiter, also if mounted at
+    /// different bases. Requests of routes without name fail with
+    /// [LimitError::MissingRouteName](crate::LimitError::MissingRouteName).
+    #[default]
+    Name,
+    /// URI template of the route including the mount base, e.g.
+    /// `/api/v1/users/<id>`.
+    ///
+    /// Every mount of a route gets its own limiter and also unnamed routes
+    /// are governed.
+    Uri,
+    /// [Name](RouteIdentity::Name) of the route or the
+    /// [URI template](RouteIdentity::Uri) for routes without name.
+    NameOrUri,
+}
+
+impl RouteIdentity {
+    /// Identity of `route`.
+    #[inline]
+    pub(crate) fn of(self, route: &Route) -> Option<&str> {
+        match self {
+            RouteIdentity::Name => route.name.as_deref(),
+            RouteIdentity::Uri => Some(route.uri.as_str()),
+            RouteIdentity::NameOrUri => {
+                Some(route.name.as_deref().unwrap_or_else(|| route.uri.as_str()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::{http::Method, route::dummy_handler};
+
+    #[test]
+    fn test_route_identity() {
+        let mut route = Route::new(Method::Get, "/users/<id>", dummy_handler)
+            .map_base(|base| format!("/api/v1{}", base))
+            .unwrap();
+
+        assert_eq!(None, RouteIdentity::Name.of(&route));
+        assert_eq!(Some("/api/v1/users/<id>"), RouteIdentity::Uri.of(&route));
+        assert_eq!(
+            Some("/api/v1/users/<id>"),
+            RouteIdentity::NameOrUri.of(&route)
+        );
+
+        route.name = Some("user".into());
+        assert_eq!(Some("user"), RouteIdentity::Name.of(&route));
+        assert_eq!(Some("/api/v1/users/<id>"), RouteIdentity::Uri.of(&route));
+        assert_eq!(Some("user"), RouteIdentity::NameOrUri.of(&route));
+    }
+}
@@ -0,0 +1,88 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{
+    rocket_governor_catcher, Adaptive, LoadMonitor, LoadSignal, Method, Quota, Registry,
+    RocketGovernable, RocketGovernor,
+};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+static OVERLOADED: AtomicBool = AtomicBool::new(false);
+
+fn load() -> f64 {
+    if OVERLOADED.load(Ordering::SeqCst) {
+        1.5
+    } else {
+        0.5
+    }
+}
+
+pub struct AdaptiveGuard;
+
+impl<'r> RocketGovernable<'r> for AdaptiveGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_minute(Self::nonzero(4u32))
+    }
+
+    fn adaptive(_method: Method, _route_name: &str) -> Option<Adaptive> {
+        Some(
+            Adaptive::new(LoadSignal::Load(load))
+                .with_bounds(0.5, 1.0)
+                .with_increase(0.5)
+                .with_interval(Duration::ZERO),
+        )
+    }
+}
+
+#[get("/")]
+fn route_adaptive(_limitguard: RocketGovernor<AdaptiveGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_adaptive])
+        .register("/", catchers![rocket_governor_catcher])
+        .attach(LoadMonitor)
+}
+
+#[test]
+fn test_adaptive() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let request = |ip: &str| {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Real-IP", ip.to_string()));
+        req.dispatch().status()
+    };
+
+    OVERLOADED.store(true, Ordering::SeqCst);
+    assert_eq!(Status::Ok, request("127.0.25.1"));
+    assert_eq!(Status::Ok, request("127.0.25.1"));
+    assert_eq!(Status::TooManyRequests, request("127.0.25.1"));
+
+    let limiter = Registry::get::<AdaptiveGuard>(Method::Get, "route_adaptive").unwrap();
+    assert_eq!(Some(0.5), limiter.multiplier());
+    assert_eq!(2, limiter.current_quota().burst_size().get());
+
+    OVERLOADED.store(false, Ordering::SeqCst);
+    for _ in 0..4 {
+        assert_eq!(Status::Ok, request("127.0.25.2"));
+    }
+    assert_eq!(Status::TooManyRequests, request("127.0.25.2"));
+    assert_eq!(Some(1.0), limiter.multiplier());
+
+    assert_eq!(0, LoadMonitor::in_flight());
+    assert!(LoadMonitor::latency() > Duration::ZERO);
+}
@@ -0,0 +1,175 @@
+#![cfg(feature = "admin")]
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    request::{FromRequest, Outcome},
+    routes, Request,
+};
+use rocket_governor::{KeyPart, Method, Quota, RocketGovernable, RocketGovernor};
+
+pub struct AdminGuard;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminGuard {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        match request.headers().get_one("x-admin-token") {
+            Some("secret") => Outcome::Success(AdminGuard),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+pub struct AdminTestGuard;
+
+impl<'r> RocketGovernable<'r> for AdminTestGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+
+    fn key_parts(_method: Method, route_name: &str) -> Vec<KeyPart> {
+        match route_name {
+            "route_item" => vec![KeyPart::Param("id")],
+            _ => vec![KeyPart::ClientIp],
+        }
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<AdminTestGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/items/<id>")]
+fn route_item(id: &str, _limitguard: RocketGovernor<AdminTestGuard>) -> String {
+    id.to_string()
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_test, route_item])
+        .mount("/admin", rocket_governor::admin::routes::<AdminGuard>())
+}
+
+fn request_test(client: &Client, ip: &str) -> Status {
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", ip.to_string()));
+    req.dispatch().status()
+}
+
+#[test]
+fn test_admin() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let token = Header::new("x-admin-token", "secret");
+
+    assert_eq!(
+        Status::Unauthorized,
+        client.get("/admin/limiters").dispatch().status()
+    );
+
+    for _ in 0..3 {
+        request_test(&client, "127.0.12.1");
+    }
+    assert_eq!(Status::TooManyRequests, request_test(&client, "127.0.12.1"));
+    assert_eq!(Status::Ok, request_test(&client, "127.0.12.2"));
+
+    let res = client
+        .get("/admin/limiters?guard=admin::AdminTestGuard")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(Status::Ok, res.status());
+    assert_eq!(
+        r#"[{"guard":"admin::AdminTestGuard","method":"GET","route":"route_test","quota":{"burst":2,"replenish_interval_ms":1800000,"expr":"2/h"},"keys":2}]"#,
+        res.into_string().unwrap()
+    );
+
+    // show key without consuming
+    for _ in 0..2 {
+        let res = client
+            .get("/admin/limiters/127.0.12.2?route=route_test&method=get")
+            .header(token.clone())
+            .dispatch();
+        assert_eq!(Status::Ok, res.status());
+        assert_eq!(
+            r#"[{"guard":"admin::AdminTestGuard","method":"GET","route":"route_test","key":"127.0.12.2","remaining":1,"wait_ms":0}]"#,
+            res.into_string().unwrap()
+        );
+    }
+
+    let res = client
+        .get("/admin/limiters/127.0.12.1?route=route_test")
+        .header(token.clone())
+        .dispatch();
+    let body = res.into_string().unwrap();
+    assert!(body.contains(r#""remaining":0"#));
+    assert!(!body.contains(r#""wait_ms":0"#));
+
+    // reset key
+    let res = client
+        .delete("/admin/limiters/127.0.12.1?route=route_test")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(Status::Ok, res.status());
+    assert_eq!(r#"{"reset":1}"#, res.into_string().unwrap());
+    assert_eq!(Status::Ok, request_test(&client, "127.0.12.1"));
+
+    // clear limiter
+    assert_eq!(Status::Ok, request_test(&client, "127.0.12.2"));
+    assert_eq!(Status::TooManyRequests, request_test(&client, "127.0.12.2"));
+    let res = client
+        .delete("/admin/limiters?route=route_test")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(r#"{"cleared":1}"#, res.into_string().unwrap());
+    assert_eq!(Status::Ok, request_test(&client, "127.0.12.2"));
+
+    // errors
+    let res = client
+        .get("/admin/limiters/no-ip")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(Status::BadRequest, res.status());
+    let res = client
+        .delete("/admin/limiters?route=unknown")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(Status::NotFound, res.status());
+
+    // the key is the parameter `id` of "a%41/b"
+    let res = client.get("/items/a%2541%2Fb").dispatch();
+    assert_eq!("a%41/b", res.into_string().unwrap());
+    let res = client
+        .get("/admin/limiters//a%2541%2Fb?route=route_item")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(Status::Ok, res.status());
+    assert_eq!(
+        r#"[{"guard":"admin::AdminTestGuard","method":"GET","route":"route_item","key":"/a%2541%2Fb","remaining":1,"wait_ms":0}]"#,
+        res.into_string().unwrap()
+    );
+    let res = client
+        .delete("/admin/limiters//a%2541%2Fb?route=route_item")
+        .header(token.clone())
+        .dispatch();
+    assert_eq!(r#"{"reset":1}"#, res.into_string().unwrap());
+
+    // clearing all limiters needs to be explicit
+    for uri in ["/admin/limiters", "/admin/limiters?all=false"] {
+        let res = client.delete(uri).header(token.clone()).dispatch();
+        assert_eq!(Status::BadRequest, res.status());
+    }
+    assert_eq!(Status::Ok, request_test(&client, "127.0.12.3"));
+    let res = client
+        .delete("/admin/limiters?all=true")
+        .header(token)
+        .dispatch();
+    assert_eq!(r#"{"cleared":2}"#, res.into_string().unwrap());
+}
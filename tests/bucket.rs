@@ -0,0 +1,95 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    post, routes,
+};
+use rocket_governor::{Method, Quota, Registry, RocketGovernable, RocketGovernor};
+use std::sync::Arc;
+
+pub struct BucketGuard;
+
+impl<'r> RocketGovernable<'r> for BucketGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+
+    fn bucket(method: Method, _route_name: &str) -> Option<&'static str> {
+        match method {
+            Method::Post | Method::Delete => Some("writes"),
+            _ => None,
+        }
+    }
+}
+
+#[get("/a")]
+fn read_a(_limitguard: RocketGovernor<BucketGuard>) -> Status {
+    Status::Ok
+}
+
+#[post("/a")]
+fn write_a(_limitguard: RocketGovernor<BucketGuard>) -> Status {
+    Status::Ok
+}
+
+#[rocket::delete("/b")]
+fn write_b(_limitguard: RocketGovernor<BucketGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build().mount("/", routes![read_a, write_a, write_b])
+}
+
+fn request_test(client: &Client, method: Method, uri: &'static str) -> Status {
+    let mut req = client.req(method, uri);
+    req.add_header(Header::new("X-Real-IP", "127.0.17.1"));
+    req.dispatch().status()
+}
+
+#[test]
+fn test_bucket() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    assert_eq!(Status::Ok, request_test(&client, Method::Post, "/a"));
+    assert_eq!(Status::Ok, request_test(&client, Method::Delete, "/b"));
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Post, "/a")
+    );
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Delete, "/b")
+    );
+    assert_eq!(Status::Ok, request_test(&client, Method::Get, "/a"));
+    assert_eq!(Status::Ok, request_test(&client, Method::Get, "/a"));
+
+    let bucket = Registry::get_bucket::<BucketGuard>("writes").unwrap();
+    assert!(bucket.is_bucket());
+    assert_eq!(None, bucket.method());
+    assert_eq!("writes", bucket.route_name());
+    assert_eq!(1, bucket.len());
+
+    let limiter_a = Registry::get::<BucketGuard>(Method::Post, "write_a").unwrap();
+    let limiter_b = Registry::get::<BucketGuard>(Method::Delete, "write_b").unwrap();
+    assert!(Arc::ptr_eq(&bucket, &limiter_a));
+    assert!(Arc::ptr_eq(&bucket, &limiter_b));
+
+    let read = Registry::get::<BucketGuard>(Method::Get, "read_a").unwrap();
+    assert!(!read.is_bucket());
+    assert!(!Arc::ptr_eq(&bucket, &read));
+
+    let limiters = Registry::limiters()
+        .into_iter()
+        .filter(|limiter| limiter.guard() == "bucket::BucketGuard")
+        .count();
+    assert_eq!(2, limiters);
+
+    assert!(Registry::get_bucket::<BucketGuard>("reads").is_none());
+}
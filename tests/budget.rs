@@ -0,0 +1,121 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::{Client, LocalResponse},
+    routes,
+};
+use rocket_governor::{
+    header, rocket_governor_catcher, Budget, BudgetPeriod, Method, Quota, Registry,
+    RocketGovernable, RocketGovernor,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct BudgetGuard;
+
+impl<'r> RocketGovernable<'r> for BudgetGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(100u32))
+    }
+
+    fn budget(_method: Method, _route_name: &str) -> Option<Budget> {
+        Some(Budget::per_day(Self::nonzero(2u32)))
+    }
+
+    #[cfg(feature = "limit_info")]
+    fn limit_info_allow(
+        _method: Option<Method>,
+        _route_name: Option<&str>,
+        _state: &rocket_governor::ReqState,
+    ) -> bool {
+        true
+    }
+}
+
+#[get("/")]
+fn route_budget(_limitguard: RocketGovernor<BudgetGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    #[allow(unused_mut)] // attach fairing only on feature limit_info
+    let mut rocket = rocket::build()
+        .mount("/", routes![route_budget])
+        .register("/", catchers![rocket_governor_catcher]);
+
+    #[cfg(feature = "limit_info")]
+    {
+        rocket = rocket.attach(rocket_governor::LimitHeaderGen);
+    }
+
+    rocket
+}
+
+fn header_secs(res: &LocalResponse<'_>, name: &str) -> u64 {
+    res.headers().get_one(name).unwrap().parse().unwrap()
+}
+
+/// Seconds until the next midnight UTC.
+fn until_midnight() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    86400 - now.as_secs() % 86400
+}
+
+fn limiter_ip() -> std::net::IpAddr {
+    "127.0.23.1".parse().unwrap()
+}
+
+#[test]
+fn test_budget() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for (status, remaining) in [
+        (Status::Ok, 1),
+        (Status::Ok, 0),
+        (Status::TooManyRequests, 0),
+    ] {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Real-IP", "127.0.23.1"));
+        let res = req.dispatch();
+        assert_eq!(status, res.status());
+
+        if status == Status::TooManyRequests {
+            let until_midnight = until_midnight();
+            let reset = header_secs(&res, header::X_RATELIMIT_BUDGET_RESET);
+            assert!(
+                reset + 2 >= until_midnight && reset <= until_midnight,
+                "{}",
+                reset
+            );
+            assert_eq!(reset, header_secs(&res, header::RETRY_AFTER));
+            assert_eq!(2, header_secs(&res, header::X_RATELIMIT_BUDGET_LIMIT));
+            assert_eq!(0, header_secs(&res, header::X_RATELIMIT_BUDGET_REMAINING));
+        } else if cfg!(feature = "limit_info") {
+            assert_eq!(2, header_secs(&res, header::X_RATELIMIT_BUDGET_LIMIT));
+            assert_eq!(
+                remaining,
+                header_secs(&res, header::X_RATELIMIT_BUDGET_REMAINING)
+            );
+        }
+    }
+
+    let limiter = Registry::get::<BudgetGuard>(Method::Get, "route_budget").unwrap();
+    assert_eq!(BudgetPeriod::Day, limiter.budget().unwrap().period());
+
+    let state = limiter.peek_key(limiter_ip());
+    assert_eq!(0, state.budget().unwrap().remaining());
+    assert!(limiter.reset_key(limiter_ip()));
+    assert_eq!(
+        2,
+        limiter.peek_key(limiter_ip()).budget().unwrap().remaining()
+    );
+
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", "127.0.23.1"));
+    assert_eq!(Status::Ok, req.dispatch().status());
+}
@@ -0,0 +1,67 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{
+    rocket_governor_catcher, Method, PerMinute, PerSecond, Registry, RocketGovernor,
+};
+
+#[get("/")]
+fn route_per_second(_limitguard: RocketGovernor<PerSecond<1>>) -> Status {
+    Status::Ok
+}
+
+#[get("/burst")]
+fn route_per_minute(_limitguard: RocketGovernor<PerMinute<30, 2>>) -> Status {
+    Status::Ok
+}
+
+#[get("/other")]
+fn route_per_minute_other(_limitguard: RocketGovernor<PerMinute<30, 3>>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount(
+            "/",
+            routes![route_per_second, route_per_minute, route_per_minute_other],
+        )
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+#[test]
+fn test_const_quota() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let request = |uri: &'static str| {
+        let mut req = client.get(uri);
+        req.add_header(Header::new("X-Real-IP", "127.0.29.1"));
+        req.dispatch().status()
+    };
+
+    assert_eq!(Status::Ok, request("/"));
+    assert_eq!(Status::TooManyRequests, request("/"));
+
+    assert_eq!(Status::Ok, request("/burst"));
+    assert_eq!(Status::Ok, request("/burst"));
+    assert_eq!(Status::TooManyRequests, request("/burst"));
+
+    for _ in 0..3 {
+        assert_eq!(Status::Ok, request("/other"));
+    }
+    assert_eq!(Status::TooManyRequests, request("/other"));
+
+    // every instantiation is registered on its own
+    let limiter = Registry::get::<PerMinute<30, 2>>(Method::Get, "route_per_minute").unwrap();
+    assert_eq!(2, limiter.current_quota().burst_size().get());
+    assert!(Registry::get::<PerMinute<30, 3>>(Method::Get, "route_per_minute").is_none());
+    assert!(Registry::get::<PerMinute<30, 3>>(Method::Get, "route_per_minute_other").is_some());
+}
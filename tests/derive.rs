@@ -0,0 +1,76 @@
+#![cfg(feature = "derive")]
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    post, routes,
+};
+use rocket_governor::{rocket_governor_catcher, Method, RocketGovernable, RocketGovernor};
+
+#[derive(RocketGovernable)]
+#[governor(
+    default = "10/s",
+    route(name = "login", method = "POST", quota = "2/min")
+)]
+#[governor(route(unchecked_name = "search", quota = "1/h"))]
+#[governor(route(unchecked_name = "upload", quota = "10/2min burst 2"))]
+pub struct DeriveGuard;
+
+#[get("/login")]
+fn login_form(_limitguard: RocketGovernor<DeriveGuard>) -> Status {
+    Status::Ok
+}
+
+#[post("/login")]
+fn login(_limitguard: RocketGovernor<DeriveGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![login_form, login])
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+#[test]
+fn test_derive_quota() {
+    let quota = |method, route_name| {
+        let quota = DeriveGuard::quota(method, route_name);
+        (
+            quota.burst_size().get(),
+            quota.replenish_interval().as_secs_f64(),
+        )
+    };
+
+    assert_eq!((2, 30.0), quota(Method::Post, "login"));
+    assert_eq!((10, 0.1), quota(Method::Get, "login"));
+    assert_eq!((1, 3600.0), quota(Method::Get, "search"));
+    assert_eq!((1, 3600.0), quota(Method::Delete, "search"));
+    assert_eq!((10, 0.1), quota(Method::Post, "logout"));
+    assert_eq!((2, 12.0), quota(Method::Put, "upload"));
+}
+
+#[test]
+fn test_derive_guard() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let request = |post: bool| {
+        let mut req = if post {
+            client.post("/login")
+        } else {
+            client.get("/login")
+        };
+        req.add_header(Header::new("X-Real-IP", "127.0.27.1"));
+        req.dispatch().status()
+    };
+
+    assert_eq!(Status::Ok, request(true));
+    assert_eq!(Status::Ok, request(true));
+    assert_eq!(Status::TooManyRequests, request(true));
+    assert_eq!(Status::Ok, request(false));
+}
@@ -0,0 +1,43 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket_governor::{Method, Quota, Registry, RocketGovernable, RocketGovernor};
+use std::{net::IpAddr, thread, time::Duration};
+
+pub struct FallbackGuard;
+
+impl<'r> RocketGovernable<'r> for FallbackGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::with_period(Duration::from_millis(1)).unwrap()
+    }
+}
+
+fn check(ip: &str) {
+    let ip: IpAddr = ip.parse().unwrap();
+    let _ = RocketGovernor::<FallbackGuard>::check(Method::Get, "fallback", ip);
+}
+
+#[test]
+fn test_sweep_without_key_sweeper() {
+    for n in 1..=999 {
+        check(&format!("fd00::{n:x}"));
+    }
+    let limiter = Registry::get::<FallbackGuard>(Method::Get, "fallback").unwrap();
+    assert_eq!(999, limiter.len());
+
+    thread::sleep(Duration::from_millis(10));
+
+    // checks of tracked keys don't count for the sweep
+    for _ in 0..10 {
+        check("fd00::1");
+    }
+    assert_eq!(999, limiter.len());
+
+    // the 1000th new key triggers the sweep of the idle keys at the next check
+    check("fd00::1:0");
+    assert_eq!(1_000, limiter.len());
+    thread::sleep(Duration::from_millis(10));
+    check("fd00::1");
+    assert_eq!(1, limiter.len());
+}
@@ -0,0 +1,60 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    local::asynchronous::Client,
+    routes, tokio, Build, Rocket,
+};
+use rocket_governor::{KeySweeper, Method, Quota, Registry, RocketGovernable, RocketGovernor};
+use std::time::Duration;
+
+pub struct SweepGuard;
+
+impl<'r> RocketGovernable<'r> for SweepGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::with_period(Duration::from_millis(200)).unwrap()
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<SweepGuard>) -> Status {
+    Status::Ok
+}
+
+fn launch_rocket() -> Rocket<Build> {
+    rocket::build()
+        .mount("/", routes![route_test])
+        .attach(KeySweeper::new(Duration::from_millis(50)))
+}
+
+async fn request_test(client: &Client, ip: &str) -> Status {
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", ip.to_string()));
+    req.dispatch().await.status()
+}
+
+#[rocket::async_test]
+async fn test_key_sweeper() {
+    let client = Client::untracked(launch_rocket())
+        .await
+        .expect("no rocket instance");
+
+    for ip in ["127.0.14.1", "127.0.14.2", "127.0.14.3"] {
+        assert_eq!(Status::Ok, request_test(&client, ip).await);
+    }
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, "127.0.14.1").await
+    );
+
+    let limiter = Registry::get::<SweepGuard>(Method::Get, "route_test").unwrap();
+    assert_eq!(3, limiter.len());
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(limiter.is_empty());
+    assert_eq!(Status::Ok, request_test(&client, "127.0.14.1").await);
+}
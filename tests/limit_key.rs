@@ -0,0 +1,228 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    post, routes,
+};
+use rocket_governor::{
+    KeyPart, LimitKey, Method, Quota, Registry, RocketGovernable, RocketGovernor,
+};
+use std::{net::IpAddr, path::PathBuf};
+
+pub struct KeyGuard;
+
+impl<'r> RocketGovernable<'r> for KeyGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+
+    fn key_parts(_method: Method, route_name: &str) -> Vec<KeyPart> {
+        match route_name {
+            "account_reset" => vec![KeyPart::Param("id")],
+            "search" => vec![KeyPart::ClientIp, KeyPart::Query("q")],
+            "file" => vec![KeyPart::ClientIp, KeyPart::Param("path")],
+            "user" => vec![KeyPart::Param("id")],
+            "unknown_param" => vec![KeyPart::Param("id")],
+            _ => vec![KeyPart::ClientIp],
+        }
+    }
+}
+
+#[post("/accounts/<id>/reset")]
+fn account_reset(id: u32, _limitguard: RocketGovernor<KeyGuard>) -> Status {
+    if id > 0 {
+        Status::Ok
+    } else {
+        Status::NotFound
+    }
+}
+
+#[get("/search?<_q>")]
+fn search(_q: Option<&str>, _limitguard: RocketGovernor<KeyGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/files/<path..>")]
+fn file(path: PathBuf, _limitguard: RocketGovernor<KeyGuard>) -> Status {
+    if path.is_relative() {
+        Status::Ok
+    } else {
+        Status::NotFound
+    }
+}
+
+#[post("/users/<id>")]
+fn user(id: u32, _limitguard: RocketGovernor<KeyGuard>) -> String {
+    id.to_string()
+}
+
+#[get("/unknown")]
+fn unknown_param(_limitguard: RocketGovernor<KeyGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount(
+            "/api",
+            routes![account_reset, search, file, user, unknown_param],
+        )
+        .mount("/", routes![user])
+}
+
+fn request_test(client: &Client, method: Method, uri: &'static str, ip: &str) -> Status {
+    let mut req = client.req(method, uri);
+    req.add_header(Header::new("X-Real-IP", ip.to_string()));
+    req.dispatch().status()
+}
+
+#[test]
+fn test_key_param() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    // per account for all clients
+    for ip in ["127.0.18.1", "127.0.18.2"] {
+        assert_eq!(
+            Status::Ok,
+            request_test(&client, Method::Post, "/api/accounts/1/reset", ip)
+        );
+    }
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Post, "/api/accounts/1/reset", "127.0.18.3")
+    );
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, Method::Post, "/api/accounts/2/reset", "127.0.18.3")
+    );
+
+    let limiter = Registry::get::<KeyGuard>(Method::Post, "account_reset").unwrap();
+    assert_eq!(2, limiter.len());
+    let key = LimitKey::new(None, ["1"]);
+    assert_eq!(0, limiter.peek_key(key.clone()).remaining());
+    assert!(limiter.reset_key(key));
+    assert_eq!(
+        2,
+        limiter
+            .peek_key("/1".parse::<LimitKey>().unwrap())
+            .remaining()
+    );
+}
+
+#[test]
+fn test_key_query() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for _ in 0..2 {
+        assert_eq!(
+            Status::Ok,
+            request_test(&client, Method::Get, "/api/search?q=a", "127.0.18.10")
+        );
+    }
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Get, "/api/search?q=a", "127.0.18.10")
+    );
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, Method::Get, "/api/search?q=b", "127.0.18.10")
+    );
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, Method::Get, "/api/search?q=a", "127.0.18.11")
+    );
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, Method::Get, "/api/search", "127.0.18.10")
+    );
+
+    let limiter = Registry::get::<KeyGuard>(Method::Get, "search").unwrap();
+    let ip: IpAddr = "127.0.18.10".parse().unwrap();
+    assert_eq!(
+        0,
+        limiter.peek_key(LimitKey::new(Some(ip), ["a"])).remaining()
+    );
+    assert_eq!(
+        1,
+        limiter.peek_key(LimitKey::new(Some(ip), [""])).remaining()
+    );
+}
+
+#[test]
+fn test_key_trailing_param() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for _ in 0..2 {
+        assert_eq!(
+            Status::Ok,
+            request_test(&client, Method::Get, "/api/files/a/b", "127.0.18.20")
+        );
+    }
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Get, "/api/files/a/b", "127.0.18.20")
+    );
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, Method::Get, "/api/files/a", "127.0.18.20")
+    );
+
+    let limiter = Registry::get::<KeyGuard>(Method::Get, "file").unwrap();
+    let ip: IpAddr = "127.0.18.20".parse().unwrap();
+    assert_eq!(
+        0,
+        limiter
+            .peek_key(LimitKey::new(Some(ip), ["a/b"]))
+            .remaining()
+    );
+}
+
+#[test]
+fn test_key_param_mounted_twice() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    // both mounts share the limiter of the route name and key on `id`
+    for uri in ["/users/1", "/api/users/1"] {
+        assert_eq!(
+            Status::Ok,
+            request_test(&client, Method::Post, uri, "127.0.18.30")
+        );
+    }
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Post, "/users/1", "127.0.18.30")
+    );
+    for uri in ["/api/users/2", "/api/users/2", "/api/users/3"] {
+        assert_eq!(
+            Status::Ok,
+            request_test(&client, Method::Post, uri, "127.0.18.30")
+        );
+    }
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, Method::Post, "/users/2", "127.0.18.30")
+    );
+
+    let limiter = Registry::get::<KeyGuard>(Method::Post, "user").unwrap();
+    assert_eq!(3, limiter.len());
+}
+
+#[test]
+fn test_key_param_unknown() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    // the route has no path parameter `id`, so the request is rejected
+    let mut req = client.get("/api/unknown");
+    req.add_header(Header::new("X-Real-IP", "127.0.18.40"));
+    let res = req.dispatch();
+    assert_eq!(Status::BadRequest, res.status());
+
+    let limiter = Registry::get::<KeyGuard>(Method::Get, "unknown_param").unwrap();
+    assert!(limiter.is_empty());
+}
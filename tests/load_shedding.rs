@@ -0,0 +1,96 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    local::blocking::{Client, LocalResponse},
+    routes, Build, Request, Rocket,
+};
+use rocket_governor::{
+    header, rocket_governor_unavailable_catcher, LoadShed, LoadShedder, NonZeroU32, Priority, Quota,
+};
+use std::time::Duration;
+
+#[get("/")]
+fn route_shed(shed: LoadShed) -> String {
+    shed.priority().as_str().to_string()
+}
+
+#[get("/health")]
+fn route_health(_shed: LoadShed) -> Status {
+    Status::Ok
+}
+
+#[get("/unguarded")]
+fn route_unguarded() -> Status {
+    Status::Ok
+}
+
+fn priority(request: &Request<'_>) -> Priority {
+    if request.uri().path() == "/health" {
+        Priority::Critical
+    } else if request.headers().get_one("X-Priority") == Some("low") {
+        Priority::Low
+    } else {
+        Priority::Normal
+    }
+}
+
+fn launch_rocket(shedder: LoadShedder) -> Rocket<Build> {
+    rocket::build()
+        .mount("/", routes![route_shed, route_health, route_unguarded])
+        .register("/", catchers![rocket_governor_unavailable_catcher])
+        .attach(shedder.with_priority(priority))
+}
+
+fn retry_after(res: &LocalResponse<'_>) -> u64 {
+    res.headers()
+        .get_one(header::RETRY_AFTER)
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn test_shed_by_rate() {
+    let shedder = LoadShedder::new().with_max_rate(
+        Priority::Low,
+        Quota::per_minute(NonZeroU32::new(2).unwrap()),
+    );
+    let client = Client::untracked(launch_rocket(shedder)).expect("no rocket instance");
+    let low = || {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Priority", "low"));
+        req.dispatch()
+    };
+
+    assert_eq!(Status::Ok, low().status());
+    assert_eq!("low", low().into_string().unwrap());
+    let res = low();
+    assert_eq!(Status::ServiceUnavailable, res.status());
+    let retry_after = retry_after(&res);
+    assert!(retry_after > 0 && retry_after <= 30, "{}", retry_after);
+
+    // other classes are not shed, although their requests count
+    assert_eq!("normal", client.get("/").dispatch().into_string().unwrap());
+    assert_eq!(Status::Ok, client.get("/health").dispatch().status());
+}
+
+#[test]
+fn test_shed_by_in_flight() {
+    let shedder = LoadShedder::new()
+        .with_max_in_flight(Priority::Normal, 0)
+        .with_max_in_flight(Priority::Critical, 0)
+        .with_retry_after(Duration::from_secs(5));
+    let client = Client::untracked(launch_rocket(shedder)).expect("no rocket instance");
+
+    let res = client.get("/").dispatch();
+    assert_eq!(Status::ServiceUnavailable, res.status());
+    assert_eq!(5, retry_after(&res));
+
+    // critical requests and routes without guard are never shed
+    assert_eq!(Status::Ok, client.get("/health").dispatch().status());
+    assert_eq!(Status::Ok, client.get("/unguarded").dispatch().status());
+}
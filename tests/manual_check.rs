@@ -0,0 +1,109 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{get, http::Header, http::Status, launch, local::blocking::Client, routes};
+use rocket_governor::{
+    observer::Decision, KeyPart, LimitKey, Method, Quota, Registry, RocketGovernable,
+    RocketGovernor,
+};
+use std::net::IpAddr;
+
+pub struct ManualGuard;
+
+impl<'r> RocketGovernable<'r> for ManualGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+
+    fn shadow(_method: Method, route_name: &str) -> bool {
+        route_name == "shadowed"
+    }
+
+    fn key_parts(_method: Method, route_name: &str) -> Vec<KeyPart> {
+        if route_name == "route_manual_item" {
+            vec![KeyPart::ClientIp, KeyPart::Param("id")]
+        } else {
+            vec![KeyPart::ClientIp]
+        }
+    }
+}
+
+#[get("/manual")]
+fn route_manual(_limitguard: RocketGovernor<ManualGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/manual/<id>")]
+fn route_manual_item(id: u32, _limitguard: RocketGovernor<ManualGuard>) -> String {
+    id.to_string()
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build().mount("/", routes![route_manual, route_manual_item])
+}
+
+#[test]
+fn test_manual_check() {
+    let client: IpAddr = "127.0.20.1".parse().unwrap();
+
+    let record = RocketGovernor::<ManualGuard>::check(Method::Get, "ws_message", client);
+    assert_eq!(Decision::Allowed, record.decision());
+    assert_eq!(Some("ws_message"), record.route_name());
+    assert_eq!(Some(&LimitKey::from(client)), record.key());
+    assert_eq!(2, record.quota().unwrap().burst_size().get());
+    assert_eq!(1, record.remaining());
+
+    let record = RocketGovernor::<ManualGuard>::check(Method::Get, "ws_message", client);
+    assert_eq!(Decision::Allowed, record.decision());
+    assert_eq!(0, record.remaining());
+
+    let record = RocketGovernor::<ManualGuard>::check(Method::Get, "ws_message", client);
+    assert_eq!(Decision::Limited, record.decision());
+    assert!(!record.wait_time().is_zero());
+
+    let limiter = Registry::get::<ManualGuard>(Method::Get, "ws_message").unwrap();
+    assert_eq!(1, limiter.len());
+
+    let key = LimitKey::new(None, ["job-queue"]);
+    for decision in [Decision::Allowed, Decision::Allowed, Decision::Limited] {
+        let record = RocketGovernor::<ManualGuard>::check(Method::Post, "jobs", &key);
+        assert_eq!(decision, record.decision());
+    }
+
+    for decision in [Decision::Allowed, Decision::Allowed, Decision::Shadowed] {
+        let record = RocketGovernor::<ManualGuard>::check(Method::Get, "shadowed", client);
+        assert_eq!(decision, record.decision());
+    }
+}
+
+#[test]
+fn test_manual_check_shared_with_guard() {
+    let rocket_client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let client: IpAddr = "127.0.20.2".parse().unwrap();
+
+    let record = RocketGovernor::<ManualGuard>::check(Method::Get, "route_manual", client);
+    assert_eq!(Decision::Allowed, record.decision());
+
+    for status in [Status::Ok, Status::TooManyRequests] {
+        let mut req = rocket_client.get("/manual");
+        req.add_header(Header::new("X-Real-IP", "127.0.20.2"));
+        assert_eq!(status, req.dispatch().status());
+    }
+
+    // registered by the manual check before the first request
+    let key = LimitKey::new(Some(client), ["7"]);
+    let record = RocketGovernor::<ManualGuard>::check(Method::Get, "route_manual_item", &key);
+    assert_eq!(Decision::Allowed, record.decision());
+
+    for status in [Status::Ok, Status::TooManyRequests] {
+        let mut req = rocket_client.get("/manual/7");
+        req.add_header(Header::new("X-Real-IP", "127.0.20.2"));
+        assert_eq!(status, req.dispatch().status());
+    }
+
+    let mut req = rocket_client.get("/manual/8");
+    req.add_header(Header::new("X-Real-IP", "127.0.20.2"));
+    assert_eq!(Status::Ok, req.dispatch().status());
+}
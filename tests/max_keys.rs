@@ -0,0 +1,66 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{Method, NonZeroUsize, Quota, Registry, RocketGovernable, RocketGovernor};
+
+pub struct MaxKeysGuard;
+
+impl<'r> RocketGovernable<'r> for MaxKeysGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(1u32))
+    }
+
+    fn max_keys(_method: Method, _route_name: &str) -> Option<NonZeroUsize> {
+        NonZeroUsize::new(10)
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<MaxKeysGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build().mount("/", routes![route_test])
+}
+
+fn request_test(client: &Client, ip: &str) -> Status {
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", ip.to_string()));
+    req.dispatch().status()
+}
+
+#[test]
+fn test_max_keys() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for n in 1..=10 {
+        let ip = format!("127.0.15.{}", n);
+        assert_eq!(Status::Ok, request_test(&client, &ip));
+        assert_eq!(Status::TooManyRequests, request_test(&client, &ip));
+    }
+
+    let limiter = Registry::get::<MaxKeysGuard>(Method::Get, "route_test").unwrap();
+    assert_eq!(NonZeroUsize::new(10), limiter.max_keys());
+    assert_eq!(10, limiter.len());
+    assert_eq!(0, limiter.evicted());
+
+    // evicts the batch of the 2 oldest keys
+    assert_eq!(Status::Ok, request_test(&client, "127.0.15.11"));
+    assert_eq!(9, limiter.len());
+    assert_eq!(2, limiter.evicted());
+
+    assert_eq!(Status::Ok, request_test(&client, "127.0.15.1"));
+    assert_eq!(Status::TooManyRequests, request_test(&client, "127.0.15.3"));
+    assert_eq!(10, limiter.len());
+    assert_eq!(2, limiter.evicted());
+}
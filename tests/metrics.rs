@@ -0,0 +1,130 @@
+#![cfg(feature = "metrics")]
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{ContentType, Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{
+    rocket_governor_catcher, rocket_governor_metrics, Method, Quota, RocketGovernable,
+    RocketGovernor,
+};
+use std::net::IpAddr;
+
+pub struct MetricsGuard;
+
+impl<'r> RocketGovernable<'r> for MetricsGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(1u32))
+    }
+}
+
+pub struct BucketMetricsGuard;
+
+impl<'r> RocketGovernable<'r> for BucketMetricsGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(2u32))
+    }
+
+    fn bucket(_method: Method, _route_name: &str) -> Option<&'static str> {
+        Some("shared")
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<MetricsGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/a")]
+fn route_a(_limitguard: RocketGovernor<BucketMetricsGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/b")]
+fn route_b(_limitguard: RocketGovernor<BucketMetricsGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_test, route_a, route_b])
+        .mount("/internal", routes![rocket_governor_metrics])
+        .register("/", catchers!(rocket_governor_catcher))
+}
+
+#[test]
+fn test_metrics() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for ip in ["127.0.9.1", "127.0.9.1", "127.0.9.2"] {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Real-IP", ip));
+        req.dispatch();
+    }
+
+    let res = client.get("/internal/metrics").dispatch();
+    assert_eq!(Status::Ok, res.status());
+    let content_type = res.content_type().unwrap();
+    assert!(content_type.media_type().exact_eq(
+        ContentType::new("text", "plain")
+            .with_params([("version", "0.0.4"), ("charset", "utf-8")])
+            .media_type()
+    ));
+
+    let body = res.into_string().unwrap();
+    let labels = r#"guard="metrics::MetricsGuard",method="GET",route="route_test""#;
+    assert!(body.contains("# TYPE rocket_governor_decisions_total counter"));
+    assert!(body.contains(&format!(
+        "rocket_governor_decisions_total{{{},bucket=\"\",decision=\"allowed\"}} 2",
+        labels
+    )));
+    assert!(body.contains(&format!(
+        "rocket_governor_decisions_total{{{},bucket=\"\",decision=\"limited\"}} 1",
+        labels
+    )));
+    assert!(body.contains(&format!("rocket_governor_keys{{{}}} 2", labels)));
+    assert!(body.contains(&format!(
+        "rocket_governor_evicted_keys_total{{{}}} 0",
+        labels
+    )));
+}
+
+#[test]
+fn test_metrics_bucket() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for uri in ["/a", "/b", "/b"] {
+        let mut req = client.get(uri);
+        req.add_header(Header::new("X-Real-IP", "127.0.9.10"));
+        req.dispatch();
+    }
+    let ip: IpAddr = "127.0.9.10".parse().unwrap();
+    let _ = RocketGovernor::<BucketMetricsGuard>::check(Method::Get, "route_a", ip);
+
+    let body = client
+        .get("/internal/metrics")
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    // decisions are counted per route of the requests sharing the bucket
+    let decisions = |route: &str, decision: &str, count: u32| {
+        format!(
+            "rocket_governor_decisions_total{{guard=\"metrics::BucketMetricsGuard\",method=\"GET\",route=\"{route}\",bucket=\"shared\",decision=\"{decision}\"}} {count}"
+        )
+    };
+    assert!(body.contains(&decisions("route_a", "allowed", 1)));
+    assert!(body.contains(&decisions("route_a", "limited", 1)));
+    assert!(body.contains(&decisions("route_b", "allowed", 1)));
+    assert!(body.contains(&decisions("route_b", "limited", 1)));
+    assert!(body.contains(
+        r#"rocket_governor_keys{guard="metrics::BucketMetricsGuard",method="",route="shared"} 1"#
+    ));
+}
@@ -0,0 +1,93 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use lazy_static::lazy_static;
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{
+    observer::{register_listener, ChannelListener, Decision, DecisionListener, DecisionRecord},
+    LimitKey, Method, Quota, RocketGovernable, RocketGovernor,
+};
+use std::{net::IpAddr, sync::Arc, sync::Mutex};
+
+#[derive(Default)]
+pub struct RecordingListener(Mutex<Vec<DecisionRecord>>);
+
+impl DecisionListener for RecordingListener {
+    fn on_decision(&self, record: &DecisionRecord) {
+        self.0.lock().unwrap().push(record.clone());
+    }
+}
+
+lazy_static! {
+    static ref RECORDER: RecordingListener = RecordingListener::default();
+}
+
+pub struct ObservedGuard;
+
+impl<'r> RocketGovernable<'r> for ObservedGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(2u32))
+    }
+
+    fn listener() -> Option<&'static dyn DecisionListener> {
+        Some(&*RECORDER)
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<ObservedGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build().mount("/", routes![route_test])
+}
+
+#[test]
+fn test_listener() {
+    let (listener, mut receiver) = ChannelListener::new(1);
+    let listener = Arc::new(listener);
+    register_listener(listener.clone());
+
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    for _ in 0..3 {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Real-IP", "127.0.10.1"));
+        req.dispatch();
+    }
+
+    let records = RECORDER.0.lock().unwrap();
+    let decisions = records.iter().map(|r| r.decision()).collect::<Vec<_>>();
+    assert_eq!(
+        vec![Decision::Allowed, Decision::Allowed, Decision::Limited],
+        decisions
+    );
+
+    let limited = &records[2];
+    assert_eq!("observer::ObservedGuard", limited.guard());
+    assert_eq!(Some(Method::Get), limited.method());
+    assert_eq!(Some("route_test"), limited.route_name());
+    assert_eq!(
+        Some("127.0.10.1".parse::<IpAddr>().unwrap()),
+        limited.key().and_then(LimitKey::client_ip)
+    );
+    assert_eq!(2, limited.quota().unwrap().burst_size().get());
+    assert_eq!(0, limited.remaining());
+    assert!(!limited.wait_time().is_zero());
+    assert_eq!(1, records[0].remaining());
+    assert!(records[0].wait_time().is_zero());
+
+    // channel has capacity of 1 record
+    let record = receiver.try_recv().unwrap();
+    assert_eq!(Decision::Allowed, record.decision());
+    assert!(receiver.try_recv().is_err());
+    assert_eq!(2, listener.dropped());
+}
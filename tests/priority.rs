@@ -0,0 +1,126 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes, Request,
+};
+use rocket_governor::{
+    rocket_governor_catcher, Algorithm, Method, Priority, Quota, Registry, RocketGovernable,
+    RocketGovernor,
+};
+
+fn priority_of(request: &Request<'_>) -> Priority {
+    match request.headers().get_one("X-Priority") {
+        Some("low") => Priority::Low,
+        Some("high") => Priority::High,
+        Some("critical") => Priority::Critical,
+        _ => Priority::Normal,
+    }
+}
+
+fn reserved(priority: Priority) -> u32 {
+    match priority {
+        Priority::High => 2,
+        Priority::Critical => 1,
+        _ => 0,
+    }
+}
+
+pub struct PriorityGuard;
+
+impl<'r> RocketGovernable<'r> for PriorityGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_minute(Self::nonzero(5u32))
+    }
+
+    fn priority(request: &Request<'_>) -> Priority {
+        priority_of(request)
+    }
+
+    fn reserved_capacity(_method: Method, _route_name: &str, priority: Priority) -> u32 {
+        reserved(priority)
+    }
+}
+
+pub struct PriorityWindowGuard;
+
+impl<'r> RocketGovernable<'r> for PriorityWindowGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_minute(Self::nonzero(5u32))
+    }
+
+    fn algorithm(_method: Method, _route_name: &str) -> Algorithm {
+        Algorithm::FixedWindow
+    }
+
+    fn priority(request: &Request<'_>) -> Priority {
+        priority_of(request)
+    }
+
+    fn reserved_capacity(_method: Method, _route_name: &str, priority: Priority) -> u32 {
+        reserved(priority)
+    }
+}
+
+#[get("/")]
+fn route_priority(_limitguard: RocketGovernor<PriorityGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/window")]
+fn route_priority_window(_limitguard: RocketGovernor<PriorityWindowGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_priority, route_priority_window])
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+fn assert_reserved(client: &Client, uri: &str, ip: &str) {
+    let request = |priority: &str| {
+        let mut req = client.get(uri);
+        req.add_header(Header::new("X-Real-IP", ip.to_string()));
+        req.add_header(Header::new("X-Priority", priority.to_string()));
+        req.dispatch().status()
+    };
+
+    // 3 of 5 requests are reserved for high and critical requests
+    assert_eq!(Status::Ok, request("low"));
+    assert_eq!(Status::Ok, request("normal"));
+    assert_eq!(Status::TooManyRequests, request("low"));
+    assert_eq!(Status::TooManyRequests, request("normal"));
+
+    // 1 of 3 requests is reserved for critical requests
+    assert_eq!(Status::Ok, request("high"));
+    assert_eq!(Status::Ok, request("high"));
+    assert_eq!(Status::TooManyRequests, request("high"));
+
+    assert_eq!(Status::Ok, request("critical"));
+    assert_eq!(Status::TooManyRequests, request("critical"));
+}
+
+#[test]
+fn test_reserved_capacity() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    assert_reserved(&client, "/", "127.0.26.1");
+
+    let limiter = Registry::get::<PriorityGuard>(Method::Get, "route_priority").unwrap();
+    assert_eq!(3, limiter.reserved_above(Priority::Low));
+    assert_eq!(1, limiter.reserved_above(Priority::High));
+    assert_eq!(0, limiter.reserved_above(Priority::Critical));
+    assert_eq!(5, limiter.current_quota().burst_size().get());
+}
+
+#[test]
+fn test_reserved_capacity_window() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    assert_reserved(&client, "/window", "127.0.26.2");
+}
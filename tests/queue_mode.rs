@@ -0,0 +1,175 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    local::asynchronous::Client,
+    routes, tokio, Build, Rocket,
+};
+use rocket_governor::{
+    Method, NonZeroUsize, QueueMode, Quota, RateLimitStatus, Registry, RocketGovernable,
+    RocketGovernor,
+};
+use std::time::{Duration, Instant};
+
+pub struct QueueGuard;
+
+impl<'r> RocketGovernable<'r> for QueueGuard {
+    fn quota(_method: Method, route_name: &str) -> Quota {
+        if route_name == "route_slow" {
+            Quota::with_period(Duration::from_secs(10)).unwrap()
+        } else if route_name == "route_queued" {
+            Quota::with_period(Duration::from_millis(200)).unwrap()
+        } else {
+            Quota::with_period(Duration::from_millis(100)).unwrap()
+        }
+    }
+
+    fn queue_mode(_method: Method, route_name: &str) -> Option<QueueMode> {
+        let max_waiting = if route_name == "route_queued" { 8 } else { 1 };
+        Some(
+            QueueMode::new(Duration::from_millis(500))
+                .with_jitter(Duration::from_millis(10))
+                .with_max_waiting(NonZeroUsize::new(max_waiting).unwrap()),
+        )
+    }
+}
+
+#[get("/queue")]
+fn route_queue(_limitguard: RocketGovernor<QueueGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/slow")]
+fn route_slow(_limitguard: RocketGovernor<QueueGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/queued")]
+fn route_queued(_limitguard: RocketGovernor<QueueGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/status")]
+fn route_status(status: RateLimitStatus<QueueGuard>) -> String {
+    status.decision().as_str().to_string()
+}
+
+fn launch_rocket() -> Rocket<Build> {
+    rocket::build().mount(
+        "/",
+        routes![route_queue, route_slow, route_queued, route_status],
+    )
+}
+
+async fn request_test(client: &Client, uri: &'static str, ip: &str) -> Status {
+    let mut req = client.get(uri);
+    req.add_header(Header::new("X-Real-IP", ip.to_string()));
+    req.dispatch().await.status()
+}
+
+#[rocket::async_test]
+async fn test_queue_delay() {
+    let client = Client::untracked(launch_rocket())
+        .await
+        .expect("no rocket instance");
+
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, "/queue", "127.0.21.1").await
+    );
+
+    let start = Instant::now();
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, "/queue", "127.0.21.1").await
+    );
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    let limiter = Registry::get::<QueueGuard>(Method::Get, "route_queue").unwrap();
+    assert_eq!(0, limiter.waiting());
+}
+
+#[rocket::async_test]
+async fn test_queue_max_waiting() {
+    let client = Client::untracked(launch_rocket())
+        .await
+        .expect("no rocket instance");
+
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, "/queue", "127.0.21.2").await
+    );
+
+    let (first, second) = tokio::join!(
+        request_test(&client, "/queue", "127.0.21.2"),
+        request_test(&client, "/queue", "127.0.21.2"),
+    );
+    let mut statuses = [first.code, second.code];
+    statuses.sort_unstable();
+    assert_eq!([200, 429], statuses);
+}
+
+#[rocket::async_test]
+async fn test_queue_max_delay() {
+    let client = Client::untracked(launch_rocket())
+        .await
+        .expect("no rocket instance");
+
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, "/slow", "127.0.21.3").await
+    );
+
+    let start = Instant::now();
+    assert_eq!(
+        Status::TooManyRequests,
+        request_test(&client, "/slow", "127.0.21.3").await
+    );
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[rocket::async_test]
+async fn test_queue_status() {
+    let client = Client::untracked(launch_rocket())
+        .await
+        .expect("no rocket instance");
+
+    for decision in ["allowed", "delayed"] {
+        let mut req = client.get("/status");
+        req.add_header(Header::new("X-Real-IP", "127.0.21.4"));
+        let res = req.dispatch().await;
+        assert_eq!(Status::Ok, res.status());
+        assert_eq!(decision, res.into_string().await.unwrap());
+    }
+}
+
+#[rocket::async_test]
+async fn test_queue_projected_wait() {
+    let client = Client::untracked(launch_rocket())
+        .await
+        .expect("no rocket instance");
+
+    assert_eq!(
+        Status::Ok,
+        request_test(&client, "/queued", "127.0.21.5").await
+    );
+
+    // waits of about 200 ms and 400 ms are allowed, the projected waits of
+    // 600 ms and 800 ms exceed the max delay and are rejected immediately
+    let timed = || async {
+        let start = Instant::now();
+        let status = request_test(&client, "/queued", "127.0.21.5").await;
+        (status.code, start.elapsed())
+    };
+    let (first, second, third, fourth) = tokio::join!(timed(), timed(), timed(), timed());
+    let mut results = [first, second, third, fourth];
+    results.sort_unstable();
+
+    assert_eq!([200, 200, 429, 429], results.map(|(status, _)| status));
+    for (_, elapsed) in &results[2..] {
+        assert!(*elapsed < Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "derive")]
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    post, routes,
+};
+use rocket_governor::{rate_limit, rocket_governor_catcher, Method, RocketGovernable};
+
+#[rate_limit(per_minute = 30, burst = 2, key = "ip")]
+#[post("/login")]
+fn login() -> Status {
+    Status::Ok
+}
+
+#[rate_limit(quota = "1/h", key = "param:id")]
+#[get("/accounts/<id>")]
+async fn account(id: u32) -> String {
+    id.to_string()
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![login, account])
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+#[test]
+fn test_rate_limit_quota() {
+    let quota = LoginRateLimit::quota(Method::Post, "login");
+    assert_eq!(2, quota.burst_size().get());
+    assert_eq!(2, quota.replenish_interval().as_secs());
+
+    let quota = AccountRateLimit::quota(Method::Get, "account");
+    assert_eq!(1, quota.burst_size().get());
+    assert_eq!(3600, quota.replenish_interval().as_secs());
+}
+
+#[test]
+fn test_rate_limit_guard() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let login = |ip: &str| {
+        let mut req = client.post("/login");
+        req.add_header(Header::new("X-Real-IP", ip.to_string()));
+        req.dispatch().status()
+    };
+
+    assert_eq!(Status::Ok, login("127.0.28.1"));
+    assert_eq!(Status::Ok, login("127.0.28.1"));
+    assert_eq!(Status::TooManyRequests, login("127.0.28.1"));
+    assert_eq!(Status::Ok, login("127.0.28.2"));
+
+    // per account for all clients
+    let account = |id: u32, ip: &str| {
+        let mut req = client.get(format!("/accounts/{id}"));
+        req.add_header(Header::new("X-Real-IP", ip.to_string()));
+        req.dispatch()
+    };
+    assert_eq!("1", account(1, "127.0.28.1").into_string().unwrap());
+    assert_eq!(Status::TooManyRequests, account(1, "127.0.28.2").status());
+    assert_eq!(Status::Ok, account(2, "127.0.28.2").status());
+}
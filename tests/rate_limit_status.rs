@@ -0,0 +1,82 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{
+    header::RETRY_AFTER, observer::Decision, rocket_governor_catcher, Method, Quota,
+    RateLimitStatus, RocketGovernable,
+};
+
+pub struct StatusGuard;
+
+impl<'r> RocketGovernable<'r> for StatusGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+}
+
+#[get("/degraded")]
+fn route_degraded(status: RateLimitStatus<StatusGuard>) -> String {
+    assert_eq!(2, status.quota().unwrap().burst_size().get());
+    if status.is_limited() {
+        assert_eq!(Decision::Limited, status.decision());
+        assert!(status.error().is_some());
+        assert!(!status.wait_time().is_zero());
+        String::from("cached")
+    } else {
+        assert_eq!(Decision::Allowed, status.decision());
+        assert!(status.error().is_none());
+        format!("fresh {}", status.remaining())
+    }
+}
+
+#[get("/reject")]
+fn route_reject(status: RateLimitStatus<StatusGuard>) -> Status {
+    if status.is_limited() {
+        Status::TooManyRequests
+    } else {
+        Status::Ok
+    }
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_degraded, route_reject])
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+#[test]
+fn test_status_degraded() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for expected in ["fresh 1", "fresh 0", "cached", "cached"] {
+        let mut req = client.get("/degraded");
+        req.add_header(Header::new("X-Real-IP", "127.0.19.1"));
+        let res = req.dispatch();
+        assert_eq!(Status::Ok, res.status());
+        assert_eq!(expected, res.into_string().unwrap());
+    }
+}
+
+#[test]
+fn test_status_reject() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for expected in [Status::Ok, Status::Ok, Status::TooManyRequests] {
+        let mut req = client.get("/reject");
+        req.add_header(Header::new("X-Real-IP", "127.0.19.2"));
+        let res = req.dispatch();
+        assert_eq!(expected, res.status());
+        if expected == Status::TooManyRequests {
+            assert!(res.headers().get_one(RETRY_AFTER).is_some());
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{Method, Quota, Registry, RocketGovernable, RocketGovernor};
+use std::{net::IpAddr, panic, time::Instant};
+
+pub struct RegistryGuard;
+
+impl<'r> RocketGovernable<'r> for RegistryGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+}
+
+pub struct PanicGuard;
+
+impl<'r> RocketGovernable<'r> for PanicGuard {
+    fn quota(_method: Method, route_name: &str) -> Quota {
+        assert_ne!("panics", route_name, "no quota");
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+
+    fn bucket(_method: Method, _route_name: &str) -> Option<&'static str> {
+        Some("shared")
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<RegistryGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build().mount("/", routes![route_test])
+}
+
+fn request_test(client: &Client, ip: &str) -> Status {
+    let mut req = client.get("/");
+    req.add_header(Header::new("X-Real-IP", ip.to_string()));
+    req.dispatch().status()
+}
+
+#[test]
+fn test_registry() {
+    assert!(Registry::get::<RegistryGuard>(Method::Get, "route_test").is_none());
+
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    assert_eq!(Status::Ok, request_test(&client, "127.0.13.1"));
+
+    let limiter = Registry::get::<RegistryGuard>(Method::Get, "route_test").unwrap();
+    assert_eq!("registry::RegistryGuard", limiter.guard());
+    assert_eq!(Some(Method::Get), limiter.method());
+    assert!(!limiter.is_bucket());
+    assert_eq!("route_test", limiter.route_name());
+    assert_eq!(2, limiter.quota().burst_size().get());
+    assert_eq!(1, limiter.len());
+    assert!(Registry::limiters()
+        .iter()
+        .any(|l| l.guard() == limiter.guard() && l.route_name() == "route_test"));
+    assert!(Registry::get::<RegistryGuard>(Method::Post, "route_test").is_none());
+
+    let key: IpAddr = "127.0.13.1".parse().unwrap();
+    let unknown: IpAddr = "127.0.13.2".parse().unwrap();
+
+    // peek doesn't consume
+    for _ in 0..2 {
+        let state = limiter.peek_key(key);
+        assert_eq!(1, state.remaining());
+        assert!(state.wait_time().is_zero());
+        assert_eq!(2, limiter.peek_key(unknown).remaining());
+    }
+    assert_eq!(1, limiter.len());
+
+    assert_eq!(Status::Ok, request_test(&client, "127.0.13.1"));
+    let state = limiter.peek_key(key);
+    assert_eq!(0, state.remaining());
+    assert!(state.wait_time().as_secs() > 29 * 60);
+    assert!(state.next_allowed() > Instant::now());
+    assert_eq!(Status::TooManyRequests, request_test(&client, "127.0.13.1"));
+
+    assert!(limiter.reset_key(key));
+    assert!(!limiter.reset_key(unknown));
+    assert_eq!(2, limiter.peek_key(key).remaining());
+    assert_eq!(Status::Ok, request_test(&client, "127.0.13.1"));
+
+    limiter.clear();
+    assert!(limiter.is_empty());
+}
+
+#[test]
+fn test_registry_hook_panics() {
+    let key: IpAddr = "127.0.13.10".parse().unwrap();
+
+    let res =
+        panic::catch_unwind(|| RocketGovernor::<PanicGuard>::check(Method::Get, "panics", key));
+    assert!(res.is_err());
+    assert!(Registry::get::<PanicGuard>(Method::Get, "panics").is_none());
+
+    // the registry is still usable
+    let _ = RocketGovernor::<PanicGuard>::check(Method::Get, "works", key);
+    assert!(Registry::get::<PanicGuard>(Method::Get, "works").is_some());
+    assert_eq!(
+        1,
+        Registry::get_bucket::<PanicGuard>("shared").unwrap().len()
+    );
+    assert!(!Registry::limiters().is_empty());
+}
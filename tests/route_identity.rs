@@ -0,0 +1,86 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{Method, Quota, Registry, RocketGovernable, RocketGovernor, RouteIdentity};
+
+pub struct UriGuard;
+
+impl<'r> RocketGovernable<'r> for UriGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(1u32))
+    }
+
+    fn route_identity() -> RouteIdentity {
+        RouteIdentity::Uri
+    }
+}
+
+pub struct NameGuard;
+
+impl<'r> RocketGovernable<'r> for NameGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(1u32))
+    }
+}
+
+#[get("/<_id>")]
+fn route_uri(_id: u32, _limitguard: RocketGovernor<UriGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/")]
+fn route_name(_limitguard: RocketGovernor<NameGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    let mut unnamed = routes![route_uri, route_name];
+    for route in &mut unnamed {
+        route.name = None;
+    }
+
+    rocket::build()
+        .mount("/a", routes![route_uri])
+        .mount("/b", routes![route_uri])
+        .mount("/unnamed", unnamed)
+}
+
+fn request_test(client: &Client, uri: &str) -> Status {
+    let mut req = client.get(uri.to_string());
+    req.add_header(Header::new("X-Real-IP", "127.0.16.1"));
+    req.dispatch().status()
+}
+
+#[test]
+fn test_route_identity_uri() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    assert_eq!(Status::Ok, request_test(&client, "/a/1"));
+    assert_eq!(Status::TooManyRequests, request_test(&client, "/a/2"));
+    assert_eq!(Status::Ok, request_test(&client, "/b/1"));
+    assert_eq!(Status::TooManyRequests, request_test(&client, "/b/1"));
+    assert_eq!(Status::Ok, request_test(&client, "/unnamed/1"));
+    assert_eq!(Status::TooManyRequests, request_test(&client, "/unnamed/1"));
+
+    for route_name in ["/a/<_id>", "/b/<_id>", "/unnamed/<_id>"] {
+        let limiter = Registry::get::<UriGuard>(Method::Get, route_name).unwrap();
+        assert_eq!(route_name, limiter.route_name());
+        assert_eq!(1, limiter.len());
+    }
+}
+
+#[test]
+fn test_route_identity_name_unnamed() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    assert_eq!(Status::BadRequest, request_test(&client, "/unnamed"));
+}
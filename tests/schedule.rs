@@ -0,0 +1,93 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+    time::OffsetDateTime,
+};
+use rocket_governor::{
+    rocket_governor_catcher, Method, Quota, QuotaSchedule, Registry, RocketGovernable,
+    RocketGovernor,
+};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// UNIX timestamp of the fake clock.
+static NOW: AtomicI64 = AtomicI64::new(0);
+
+/// 2023-01-02 is a Monday.
+const MONDAY_10H: i64 = 1_672_653_600;
+const MONDAY_21H: i64 = MONDAY_10H + 11 * 3600;
+
+fn fake_clock() -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(NOW.load(Ordering::SeqCst)).unwrap()
+}
+
+pub struct ScheduleGuard;
+
+impl<'r> RocketGovernable<'r> for ScheduleGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_minute(Self::nonzero(4u32))
+    }
+
+    fn quota_schedule(_method: Method, _route_name: &str) -> Option<QuotaSchedule> {
+        Some(
+            QuotaSchedule::new()
+                .with_quota(
+                    &QuotaSchedule::WEEKDAYS,
+                    8,
+                    20,
+                    Quota::per_minute(Self::nonzero(2u32)),
+                )
+                .with_clock(fake_clock),
+        )
+    }
+}
+
+#[get("/")]
+fn route_schedule(_limitguard: RocketGovernor<ScheduleGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_schedule])
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+#[test]
+fn test_schedule() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let request = || {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Real-IP", "127.0.24.1"));
+        req.dispatch().status()
+    };
+
+    NOW.store(MONDAY_10H, Ordering::SeqCst);
+    assert_eq!(Status::Ok, request());
+    assert_eq!(Status::Ok, request());
+    assert_eq!(Status::TooManyRequests, request());
+
+    let limiter = Registry::get::<ScheduleGuard>(Method::Get, "route_schedule").unwrap();
+    assert_eq!(2, limiter.current_quota().burst_size().get());
+    assert_eq!(4, limiter.quota().burst_size().get());
+
+    // the requests of the scheduled quota count for the quota of the limiter
+    let ip: std::net::IpAddr = "127.0.24.1".parse().unwrap();
+    NOW.store(MONDAY_21H, Ordering::SeqCst);
+    assert_eq!(4, limiter.current_quota().burst_size().get());
+    assert!(limiter.peek_key(ip).wait_time().as_secs() >= 29);
+    assert_eq!(Status::TooManyRequests, request());
+
+    assert!(limiter.reset_key(ip));
+    for _ in 0..4 {
+        assert_eq!(Status::Ok, request());
+    }
+    assert_eq!(Status::TooManyRequests, request());
+}
@@ -38,6 +38,17 @@ impl<'r> RocketGovernable<'r> for RateLimitGGuard {
     }
 }
 
+pub struct RateLimitShadowGuard;
+impl<'r> RocketGovernable<'r> for RateLimitShadowGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(1u32))
+    }
+
+    fn shadow(_method: Method, _route_name: &str) -> bool {
+        true
+    }
+}
+
 #[get("/")]
 fn route_test(_limitguard: RocketGovernor<RateLimitGuard>) -> Status {
     Status::Ok
@@ -48,6 +59,11 @@ fn route_member(_limitguard: RocketGovernor<RateLimitGuardWithMember>) -> Status
     Status::Ok
 }
 
+#[get("/shadow")]
+fn route_shadow(_limitguard: RocketGovernor<RateLimitShadowGuard>) -> Status {
+    Status::Ok
+}
+
 mod guard2 {
     use rocket::{get, http::Status};
     use rocket_governor::{Method, Quota, RocketGovernable, RocketGovernor};
@@ -84,7 +100,7 @@ mod guard2 {
 fn launch_rocket() -> _ {
     #[allow(unused_mut)] // attach fairing only on feature limit_info
     let mut r = rocket::build()
-        .mount("/", routes![route_test, route_member])
+        .mount("/", routes![route_test, route_member, route_shadow])
         .register("/", catchers!(rocket_governor_catcher))
         .mount(
             "/guard2",
@@ -339,3 +355,41 @@ fn test_ratelimit_info_header() {
     let retry_header = res.headers().get_one(rg_header::RETRY_AFTER);
     assert_ne!(None, retry_header);
 }
+
+#[test]
+fn test_ratelimit_shadow() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    for _ in 0..3 {
+        let mut req = client.get("/shadow");
+        req.add_header(Header::new("X-Real-IP", "127.0.7.1"));
+        let res = req.dispatch();
+
+        assert_eq!(Status::Ok, res.status());
+        assert_eq!(None, res.headers().get_one(rg_header::RETRY_AFTER));
+    }
+}
+
+#[cfg(feature = "limit_info")]
+#[test]
+fn test_ratelimit_shadow_header() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    let mut req = client.get("/shadow");
+    req.add_header(Header::new("X-Real-IP", "127.0.8.1"));
+    let res = req.dispatch();
+    assert_eq!(Status::Ok, res.status());
+
+    let shadow_header = res.headers().get_one(rg_header::X_RATELIMIT_SHADOW);
+    assert_eq!(None, shadow_header);
+
+    let mut req = client.get("/shadow");
+    req.add_header(Header::new("X-Real-IP", "127.0.8.1"));
+    let res = req.dispatch();
+    assert_eq!(Status::Ok, res.status());
+
+    let shadow_header = res.headers().get_one(rg_header::X_RATELIMIT_SHADOW);
+    assert_ne!(None, shadow_header);
+    // the wait of less than a second is rounded up
+    assert_eq!(1, u64::from_str(shadow_header.unwrap()).unwrap());
+    let remain_header = res.headers().get_one(rg_header::X_RATELIMIT_REMAINING);
+    assert_eq!(Some("0"), remain_header);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "tracing")]
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    get,
+    http::{Header, Status},
+    launch,
+    local::blocking::Client,
+    routes,
+};
+use rocket_governor::{Method, Quota, RocketGovernable, RocketGovernor};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+type Fields = HashMap<String, String>;
+
+/// Subscriber recording the fields of all events.
+#[derive(Clone, Default)]
+struct EventRecorder {
+    events: Arc<Mutex<Vec<Fields>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let _ = self
+            .0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let _ = self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl Subscriber for EventRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+pub struct TracedGuard;
+
+impl<'r> RocketGovernable<'r> for TracedGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_second(Self::nonzero(1u32))
+    }
+}
+
+#[get("/")]
+fn route_test(_limitguard: RocketGovernor<TracedGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build().mount("/", routes![route_test])
+}
+
+#[test]
+fn test_tracing_events() {
+    let recorder = EventRecorder::default();
+    tracing::subscriber::set_global_default(recorder.clone()).unwrap();
+
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+    for _ in 0..2 {
+        let mut req = client.get("/");
+        req.add_header(Header::new("X-Real-IP", "127.0.11.1"));
+        req.dispatch();
+    }
+
+    let events = recorder.events.lock().unwrap();
+    let decisions = events
+        .iter()
+        .filter(|fields| fields.contains_key("decision"))
+        .collect::<Vec<_>>();
+    assert_eq!(2, decisions.len());
+
+    let limited = decisions[1];
+    assert_eq!("limited", limited["decision"]);
+    assert_eq!("tracing::TracedGuard", limited["guard"]);
+    assert_eq!("GET", limited["method"]);
+    assert_eq!("route_test", limited["route"]);
+    assert_eq!("127.0.11.1", limited["key"]);
+    assert_eq!("0", limited["remaining"]);
+    assert!(limited["wait_ms"].parse::<u64>().unwrap() > 0);
+
+    assert!(events
+        .iter()
+        .any(|fields| fields.get("message").map(String::as_str) == Some("new limiter")));
+}
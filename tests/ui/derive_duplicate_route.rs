@@ -0,0 +1,9 @@
+use rocket_governor::RocketGovernable;
+
+#[derive(RocketGovernable)]
+#[governor(default = "10/s")]
+#[governor(route(unchecked_name = "login", quota = "5/min"))]
+#[governor(route(unchecked_name = "login", method = "POST", quota = "1/min"))]
+pub struct RateLimitGuard;
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rocket_governor::RocketGovernable;
+
+#[derive(RocketGovernable)]
+#[governor(default = "10/s")]
+#[governor(route(unchecked_name = "login", method = "FETCH", quota = "5/min"))]
+pub struct RateLimitGuard;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use rocket_governor::RocketGovernable;
+
+#[derive(RocketGovernable)]
+#[governor(route(unchecked_name = "login", quota = "5/min"))]
+pub struct RateLimitGuard;
+
+fn main() {}
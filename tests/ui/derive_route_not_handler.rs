@@ -0,0 +1,7 @@
+use rocket_governor::RocketGovernable;
+
+#[derive(RocketGovernable)]
+#[governor(default = "10/s", route(name = "/api/login", quota = "5/min"))]
+pub struct RateLimitGuard;
+
+fn main() {}
@@ -0,0 +1,13 @@
+use rocket::{http::Status, post};
+use rocket_governor::RocketGovernable;
+
+#[derive(RocketGovernable)]
+#[governor(default = "10/s", route(name = "logn", quota = "5/min"))]
+pub struct RateLimitGuard;
+
+#[post("/login")]
+fn login() -> Status {
+    Status::Ok
+}
+
+fn main() {}
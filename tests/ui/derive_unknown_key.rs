@@ -0,0 +1,7 @@
+use rocket_governor::RocketGovernable;
+
+#[derive(RocketGovernable)]
+#[governor(default = "10/s", rate = "5/min")]
+pub struct RateLimitGuard;
+
+fn main() {}
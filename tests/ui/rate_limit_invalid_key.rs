@@ -0,0 +1,9 @@
+use rocket_governor::rate_limit;
+
+#[rate_limit(per_minute = 30, key = "cookie:session")]
+#[rocket::post("/login")]
+fn login() -> rocket::http::Status {
+    rocket::http::Status::Ok
+}
+
+fn main() {}
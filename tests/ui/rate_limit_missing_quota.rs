@@ -0,0 +1,9 @@
+use rocket_governor::rate_limit;
+
+#[rate_limit(burst = 5)]
+#[rocket::post("/login")]
+fn login() -> rocket::http::Status {
+    rocket::http::Status::Ok
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rocket_governor::rate_limit;
+
+#[rate_limit(per_second = 2_000_000_000)]
+#[rocket::post("/login")]
+fn login() -> rocket::http::Status {
+    rocket::http::Status::Ok
+}
+
+fn main() {}
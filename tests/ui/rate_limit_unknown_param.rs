@@ -0,0 +1,10 @@
+use rocket_governor::rate_limit;
+
+#[rate_limit(per_minute = 30, key = "param:id")]
+#[rocket::get("/accounts/<account>")]
+fn account(account: u32) -> rocket::http::Status {
+    let _ = account;
+    rocket::http::Status::Ok
+}
+
+fn main() {}
@@ -0,0 +1,113 @@
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![deny(clippy::all)]
+
+use rocket::{
+    catchers, get,
+    http::{Header, Status},
+    launch,
+    local::blocking::{Client, LocalResponse},
+    routes,
+};
+use rocket_governor::{
+    header, rocket_governor_catcher, Algorithm, Method, Quota, Registry, RocketGovernable,
+    RocketGovernor,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct WindowGuard;
+
+impl<'r> RocketGovernable<'r> for WindowGuard {
+    fn quota(_method: Method, _route_name: &str) -> Quota {
+        Quota::per_hour(Self::nonzero(2u32))
+    }
+
+    fn algorithm(_method: Method, route_name: &str) -> Algorithm {
+        match route_name {
+            "route_fixed" => Algorithm::FixedWindow,
+            _ => Algorithm::SlidingWindow,
+        }
+    }
+}
+
+#[get("/fixed")]
+fn route_fixed(_limitguard: RocketGovernor<WindowGuard>) -> Status {
+    Status::Ok
+}
+
+#[get("/sliding")]
+fn route_sliding(_limitguard: RocketGovernor<WindowGuard>) -> Status {
+    Status::Ok
+}
+
+#[launch]
+fn launch_rocket() -> _ {
+    rocket::build()
+        .mount("/", routes![route_fixed, route_sliding])
+        .register("/", catchers![rocket_governor_catcher])
+}
+
+fn header_secs(res: &LocalResponse<'_>, name: &str) -> u64 {
+    res.headers().get_one(name).unwrap().parse().unwrap()
+}
+
+/// Seconds until the end of the current calendar hour.
+fn until_end_of_hour() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    3600 - now.as_secs() % 3600
+}
+
+fn limiter_ip() -> std::net::IpAddr {
+    "127.0.22.1".parse().unwrap()
+}
+
+#[test]
+fn test_fixed_window() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for status in [Status::Ok, Status::Ok, Status::TooManyRequests] {
+        let mut req = client.get("/fixed");
+        req.add_header(Header::new("X-Real-IP", "127.0.22.1"));
+        let res = req.dispatch();
+        assert_eq!(status, res.status());
+
+        if status == Status::TooManyRequests {
+            let until_end = until_end_of_hour();
+            let reset = header_secs(&res, header::X_RATELIMIT_RESET);
+            assert!(reset + 2 >= until_end && reset <= until_end, "{}", reset);
+            assert_eq!(reset, header_secs(&res, header::RETRY_AFTER));
+            assert_eq!(2, header_secs(&res, header::X_RATELIMIT_LIMIT));
+        }
+    }
+
+    let limiter = Registry::get::<WindowGuard>(Method::Get, "route_fixed").unwrap();
+    assert_eq!(Algorithm::FixedWindow, limiter.algorithm());
+    assert_eq!(1, limiter.len());
+    assert_eq!(0, limiter.peek_key(limiter_ip()).remaining());
+    assert!(limiter.reset_key(limiter_ip()));
+    assert_eq!(2, limiter.peek_key(limiter_ip()).remaining());
+}
+
+#[test]
+fn test_sliding_window() {
+    let client = Client::untracked(launch_rocket()).expect("no rocket instance");
+
+    for status in [Status::Ok, Status::Ok, Status::TooManyRequests] {
+        let mut req = client.get("/sliding");
+        req.add_header(Header::new("X-Real-IP", "127.0.22.2"));
+        let res = req.dispatch();
+        assert_eq!(status, res.status());
+
+        if status == Status::TooManyRequests {
+            // counts of this window weigh half in the next window
+            let until_end = until_end_of_hour();
+            let retry_after = header_secs(&res, header::RETRY_AFTER);
+            assert!(retry_after + 2 >= until_end + 1800 && retry_after <= until_end + 1800);
+            let reset = header_secs(&res, header::X_RATELIMIT_RESET);
+            assert!(reset + 2 >= until_end + 3600 && reset <= until_end + 3600);
+        }
+    }
+
+    let limiter = Registry::get::<WindowGuard>(Method::Get, "route_sliding").unwrap();
+    assert_eq!(Algorithm::SlidingWindow, limiter.algorithm());
+}